@@ -0,0 +1,66 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rip::{parse_version, sort_versions, Version};
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+/// Versions the way they show up in a package index: mostly plain releases, some pre-, post- and dev-releases
+fn index_versions() -> Vec<String> {
+    let mut versions = Vec::new();
+    for major in 0..20 {
+        for minor in 0..25 {
+            for suffix in ["", ".0", "a1", "b2", "rc1", ".post1", ".dev0", "+cpu"] {
+                versions.push(format!("{}.{}{}", major, minor, suffix));
+            }
+        }
+    }
+    versions
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, versions) in [("index", index_versions()), ("mixed", common::version_corpus(4000))] {
+        group.bench_with_input(BenchmarkId::new("hand_written", name), &versions, |b, versions| {
+                 b.iter(|| {
+                      for version in versions {
+                          let _ = black_box(parse_version(version.clone()));
+                      }
+                  })
+             });
+        group.bench_with_input(BenchmarkId::new("regex", name), &versions, |b, versions| {
+                 b.iter(|| {
+                      for version in versions {
+                          let _ = black_box(common::regex_parse_version(version.clone()));
+                      }
+                  })
+             });
+    }
+    group.finish();
+}
+
+fn compare(c: &mut Criterion) {
+    let versions = index_versions().into_iter()
+                                   .rev()
+                                   .filter(|version| !version.contains('+'))
+                                   .map(|version| parse_version(version).unwrap())
+                                   .collect::<Vec<Version>>();
+    let mut group = c.benchmark_group("sort");
+    group.bench_function("ord", |b| {
+             b.iter(|| {
+                  let mut versions = versions.clone();
+                  versions.sort();
+                  black_box(versions)
+              })
+         });
+    group.bench_function("sort_versions", |b| {
+             b.iter(|| {
+                  let mut versions = versions.clone();
+                  sort_versions(&mut versions);
+                  black_box(versions)
+              })
+         });
+    group.finish();
+}
+
+criterion_group!(benches, parse, compare);
+criterion_main!(benches);
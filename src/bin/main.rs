@@ -1,3 +1,78 @@
-fn main() {
-    println!("Hello, world!");
+use std::process::ExitCode;
+
+use clap::{value_parser, Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use rip::bump::{bump, with_local, Bump};
+use rip::{Error, PreReleaseType, Version};
+
+fn version_bump_command() -> Command {
+    let parts = ["major", "minor", "micro", "alpha", "beta", "rc", "post", "dev"];
+    Command::new("bump").about("Print the next version")
+                        .arg(Arg::new("version").required(true).value_parser(value_parser!(Version)))
+                        .arg(Arg::new("part").value_parser(parts)
+                                             .num_args(1..)
+                                             .help("The parts of the version to advance, in order"))
+                        .arg(Arg::new("local").long("local")
+                                              .value_name("LABEL")
+                                              .help("Set the local version label"))
+                        .arg(Arg::new("strip-local").long("strip-local")
+                                                    .action(ArgAction::SetTrue)
+                                                    .conflicts_with("local")
+                                                    .help("Remove the local version label"))
+                        .group(ArgGroup::new("change").args(["part", "local", "strip-local"])
+                                                      .multiple(true)
+                                                      .required(true))
+}
+
+fn cli() -> Command {
+    Command::new("rip").about("A subset of Python's pip")
+                       .subcommand_required(true)
+                       .subcommand(Command::new("version").about("Work with PEP 440 versions")
+                                                          .subcommand_required(true)
+                                                          .subcommand(version_bump_command()))
+}
+
+fn version_bump(matches: &ArgMatches) -> Result<Version, Error> {
+    let mut version = matches.get_one::<Version>("version").unwrap().clone();
+    if let Some(parts) = matches.get_many::<String>("part") {
+        let parts = parts.map(|part| match part.as_str() {
+                             "major" => Bump::Major,
+                             "minor" => Bump::Minor,
+                             "micro" => Bump::Micro,
+                             "alpha" => Bump::Pre(PreReleaseType::Alpha),
+                             "beta" => Bump::Pre(PreReleaseType::Beta),
+                             "rc" => Bump::Pre(PreReleaseType::Rc),
+                             "post" => Bump::Post,
+                             _ => Bump::Dev,
+                         })
+                         .collect::<Vec<Bump>>();
+        version = bump(&version, &parts)?;
+    }
+    if let Some(local) = matches.get_one::<String>("local") {
+        version = with_local(&version, local)?;
+    }
+    if matches.get_flag("strip-local") {
+        version = version.public();
+    }
+    Ok(version)
+}
+
+fn main() -> ExitCode {
+    let matches = cli().get_matches();
+    let result = match matches.subcommand() {
+        Some(("version", matches)) => match matches.subcommand() {
+            Some(("bump", matches)) => version_bump(matches),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    match result {
+        Ok(version) => {
+            println!("{}", version);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,181 @@
+use crate::{parse_local_version, Error, PreReleaseType, Release, Version};
+
+/// A part of a version to advance, see `bump`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Micro,
+    Pre(PreReleaseType),
+    Post,
+    Dev,
+}
+
+/// Building a version from its parts, with `version_string` set to the normalized form
+pub(crate) fn normalized(epoch: Option<u64>, release: Release, pre: Option<(PreReleaseType, u64)>, post: Option<u64>,
+                         dev: Option<u64>)
+                         -> Version {
+    let mut version = Version { epoch,
+                                release,
+                                pre,
+                                post,
+                                dev,
+                                local: None,
+                                version_string: String::new() };
+    version.version_string = version.to_string();
+    version
+}
+
+fn invalid_bump(version: &Version, reason: &str) -> Error {
+    Error::InvalidBump { version: version.to_string(),
+                         reason: reason.to_string() }
+}
+
+/// Adding one to a number of `version`, which fails for the largest number a version can hold
+fn increment(version: &Version, n: u64) -> Result<u64, Error> {
+    n.checked_add(1)
+     .ok_or_else(|| invalid_bump(version, &format!("{} cannot be advanced any further", n)))
+}
+
+/// Advancing the release segment at `index`, resetting everything after it
+///
+/// With `finalize`, a pre-release or developmental release whose segments after `index` are all zero is finalized
+/// instead, so bumping the minor version of `1.2.0rc1` gives `1.2.0` rather than `1.3.0`.
+fn bump_release(version: &Version, index: usize, finalize: bool) -> Result<Version, Error> {
+    let mut release = version.release.clone();
+    if release.len() <= index {
+        release.resize(index + 1, 0);
+    }
+    let is_unreleased = version.is_prerelease() && version.post.is_none();
+    if !(finalize && is_unreleased && release[index + 1..].iter().all(|n| *n == 0)) {
+        release[index] = increment(version, release[index])?;
+        for n in release[index + 1..].iter_mut() {
+            *n = 0;
+        }
+    }
+    Ok(normalized(version.epoch, release, None, None, None))
+}
+
+/// Getting the next major release, e.g. `1.2.3` becomes `2.0.0`
+pub fn bump_major(version: &Version) -> Result<Version, Error> {
+    bump_release(version, 0, true)
+}
+
+/// Getting the next minor release, e.g. `1.2.3` becomes `1.3.0`
+pub fn bump_minor(version: &Version) -> Result<Version, Error> {
+    bump_release(version, 1, true)
+}
+
+/// Getting the next micro release, e.g. `1.2.3` becomes `1.2.4`
+pub fn bump_micro(version: &Version) -> Result<Version, Error> {
+    bump_release(version, 2, true)
+}
+
+fn bump_pre_release(version: &Version, pre_type: PreReleaseType, finalize: bool) -> Result<Version, Error> {
+    let pre_n = match (&version.pre, version.post, version.dev) {
+        (Some((current, pre_n)), None, Some(_)) if *current == pre_type && finalize => *pre_n,
+        (Some((current, pre_n)), _, _) if *current == pre_type => increment(version, *pre_n)?,
+        (Some((current, _)), _, _) if *current > pre_type => {
+            return Err(invalid_bump(version,
+                                    &format!("{} is an earlier pre-release series than {}",
+                                             pre_type, current)))
+        }
+        (Some(_), _, _) | (None, None, Some(_)) => 1,
+        (None, _, _) => {
+            return Err(invalid_bump(version,
+                                    "a pre-release of an already final release would be older; bump the release first"))
+        }
+    };
+    Ok(normalized(version.epoch,
+                  version.release.clone(),
+                  Some((pre_type, pre_n)),
+                  None,
+                  None))
+}
+
+/// Starting or advancing a pre-release series, e.g. `1.0a1` becomes `1.0a2` for `Alpha` and `1.0b1` for `Beta`
+///
+/// A developmental release of a pre-release is finalized instead, so `1.0rc1.dev2` becomes `1.0rc1`. Since every
+/// pre-release sorts before its final release, starting a series on a final release is an error, use `bump` with a release
+/// part followed by a pre-release part instead. Moving to an earlier series (`rc` to `a`) is an error too.
+pub fn bump_pre(version: &Version, pre_type: PreReleaseType) -> Result<Version, Error> {
+    bump_pre_release(version, pre_type, true)
+}
+
+fn bump_post_release(version: &Version, finalize: bool) -> Result<Version, Error> {
+    let post = match (version.post, version.dev) {
+        (Some(post), Some(_)) if finalize => post,
+        (Some(post), _) => increment(version, post)?,
+        (None, _) => 1,
+    };
+    Ok(normalized(version.epoch,
+                  version.release.clone(),
+                  version.pre.clone(),
+                  Some(post),
+                  None))
+}
+
+/// Appending or advancing a post-release, e.g. `1.0` becomes `1.0.post1` and `1.0.post1` becomes `1.0.post2`
+///
+/// A developmental release of a post-release is finalized instead, so `1.0.post2.dev0` becomes `1.0.post2`.
+pub fn bump_post(version: &Version) -> Result<Version, Error> {
+    bump_post_release(version, true)
+}
+
+fn append_dev(version: &Version, dev: u64) -> Version {
+    normalized(version.epoch,
+               version.release.clone(),
+               version.pre.clone(),
+               version.post,
+               Some(dev))
+}
+
+/// Advancing a developmental release, e.g. `1.1.dev0` becomes `1.1.dev1`
+///
+/// Since a developmental release sorts before the release it leads up to, appending one to any other version is an
+/// error, use `bump` with another part followed by a developmental part instead.
+pub fn bump_dev(version: &Version) -> Result<Version, Error> {
+    match version.dev {
+        Some(dev) => Ok(append_dev(version, increment(version, dev)?)),
+        None => Err(invalid_bump(version,
+                                 "a developmental release of an already released version would be older; bump another \
+                                  part first")),
+    }
+}
+
+/// Advancing each of `parts` of `version` in order, dropping its local label
+///
+/// Every part but the last always advances a number rather than finalizing, so the result is newer than `version`. A
+/// pre-release or developmental part right after another part starts a new series, so `[Minor, Pre(Rc)]` turns `1.0`
+/// into `1.1rc1` and `[Post, Dev]` turns `1.0` into `1.0.post1.dev0`.
+pub fn bump(version: &Version, parts: &[Bump]) -> Result<Version, Error> {
+    let mut bumped = version.public();
+    let mut previous: Option<&Bump> = None;
+    for (index, part) in parts.iter().enumerate() {
+        let finalize = index + 1 == parts.len();
+        bumped = match (previous, part) {
+            (Some(Bump::Major | Bump::Minor | Bump::Micro), Bump::Pre(pre_type)) => {
+                normalized(bumped.epoch, bumped.release.clone(), Some((pre_type.clone(), 1)), None, None)
+            }
+            (Some(_), Bump::Dev) if bumped.dev.is_none() => append_dev(&bumped, 0),
+            (_, Bump::Major) => bump_release(&bumped, 0, finalize)?,
+            (_, Bump::Minor) => bump_release(&bumped, 1, finalize)?,
+            (_, Bump::Micro) => bump_release(&bumped, 2, finalize)?,
+            (_, Bump::Pre(pre_type)) => bump_pre_release(&bumped, pre_type.clone(), finalize)?,
+            (_, Bump::Post) => bump_post_release(&bumped, finalize)?,
+            (_, Bump::Dev) => bump_dev(&bumped)?,
+        };
+        previous = Some(part);
+    }
+    Ok(bumped)
+}
+
+/// Replacing the local label of `version`, e.g. `1.0+cu118` becomes `1.0+cu121`
+///
+/// Use `Version::public` to strip the label instead.
+pub fn with_local(version: &Version, local: &str) -> Result<Version, Error> {
+    let mut version = Version { local: Some(parse_local_version(local.to_string())?),
+                                ..version.clone() };
+    version.version_string = version.to_string();
+    Ok(version)
+}
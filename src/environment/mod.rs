@@ -0,0 +1,147 @@
+#[cfg(feature = "environment")]
+use std::fs;
+#[cfg(feature = "environment")]
+use std::ops::Range;
+#[cfg(feature = "environment")]
+use std::path::Path;
+use std::process::Command;
+
+#[cfg(feature = "environment")]
+use serde_json::error::Category;
+
+use crate::marker::MarkerEnvironment;
+use crate::Error;
+
+impl MarkerEnvironment {
+    /// Reading a target description from a file, as TOML if its name ends in `.toml` and as JSON otherwise
+    ///
+    /// The file holds every variable except `extra` as a string, e.g. `{"python_full_version": "3.8.10", ...}` or
+    /// `python_full_version = "3.8.10"`.
+    #[cfg(feature = "environment")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let environment_string = fs::read_to_string(path).map_err(|error| Error::UnreadableFile { path: path.display()
+                                                                                                            .to_string(),
+                                                                                                  reason: error.to_string() })?;
+        match path.extension() {
+            Some(extension) if extension == "toml" => parse_marker_environment_toml(environment_string),
+            _ => parse_marker_environment_json(environment_string),
+        }
+    }
+
+    /// Detecting the environment of a local interpreter by running it, e.g. `python3` or `/usr/bin/python3.8`
+    pub fn from_interpreter(interpreter: &str) -> Result<Self, Error> {
+        let failed = |reason: String| Error::InterpreterFailed { interpreter: interpreter.to_string(),
+                                                                 reason };
+        let output = Command::new(interpreter).args(["-c", ENVIRONMENT_SCRIPT])
+                                              .output()
+                                              .map_err(|error| failed(error.to_string()))?;
+        if !output.status.success() {
+            return Err(failed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        let environment_string = String::from_utf8(output.stdout).map_err(|error| failed(error.to_string()))?;
+        let values = environment_string.split('\0').map(str::to_string).collect::<Vec<String>>();
+        match <[String; 11]>::try_from(values) {
+            Ok([implementation_name, implementation_version, os_name, platform_machine, platform_python_implementation, platform_release, platform_system, platform_version, python_full_version, python_version, sys_platform]) => {
+                Ok(MarkerEnvironment { implementation_name,
+                                       implementation_version,
+                                       os_name,
+                                       platform_machine,
+                                       platform_python_implementation,
+                                       platform_release,
+                                       platform_system,
+                                       platform_version,
+                                       python_full_version,
+                                       python_version,
+                                       sys_platform })
+            }
+            Err(values) => Err(failed(format!("printed {} values instead of 11", values.len()))),
+        }
+    }
+}
+
+/// Printing the interpreter's environment the way `packaging.markers.default_environment` computes it, as the values
+/// of the fields of `MarkerEnvironment` in order, separated by NUL characters
+const ENVIRONMENT_SCRIPT: &str = r#"
+import os, platform, sys
+
+def format_full_version(info):
+    version = "{0.major}.{0.minor}.{0.micro}".format(info)
+    if info.releaselevel != "final":
+        version += info.releaselevel[0] + str(info.serial)
+    return version
+
+sys.stdout.write("\0".join([
+    sys.implementation.name,
+    format_full_version(sys.implementation.version),
+    os.name,
+    platform.machine(),
+    platform.python_implementation(),
+    platform.release(),
+    platform.system(),
+    platform.version(),
+    platform.python_version(),
+    ".".join(platform.python_version_tuple()[:2]),
+    sys.platform,
+]))
+"#;
+
+/// The span of the character at `position`, or an empty span at the end of the input
+#[cfg(feature = "environment")]
+fn char_span(input: &str, position: usize) -> Range<usize> {
+    let position = position.min(input.len());
+    let width = input[position..].chars().next().map_or(0, char::len_utf8);
+    position..position + width
+}
+
+/// Parsing a target description written as a JSON object of strings, e.g. the output of
+/// `python -c "import json; from packaging.markers import default_environment; print(json.dumps(default_environment()))"`
+///
+/// Args:
+/// - `environment_string` (String): A JSON object with a string for every marker variable except `extra`, other keys
+///   are ignored
+///
+/// Returns `Result<MarkerEnvironment, Error>`: `Error::InvalidMarkerEnvironment` for syntax errors as well as repeated,
+/// missing or non-string variables
+#[cfg(feature = "environment")]
+pub fn parse_marker_environment_json(environment_string: String) -> Result<MarkerEnvironment, Error> {
+    serde_json::from_str(&environment_string).map_err(|error| json_error(environment_string, error))
+}
+
+/// Moving a serde_json error's line and column into a span and out of its message
+#[cfg(feature = "environment")]
+fn json_error(environment_string: String, error: serde_json::Error) -> Error {
+    // serde_json counts lines and columns from 1, a column of 0 points before the first character of the line
+    let line_start: usize = environment_string.split_inclusive('\n')
+                                              .take(error.line().saturating_sub(1))
+                                              .map(str::len)
+                                              .sum();
+    let position = match error.classify() {
+        Category::Eof => environment_string.len(),
+        _ => line_start + error.column().saturating_sub(1),
+    };
+    let location = format!(" at line {} column {}", error.line(), error.column());
+    let reason = error.to_string();
+    let reason = reason.strip_suffix(&location).unwrap_or(&reason).to_string();
+    Error::InvalidMarkerEnvironment { span: char_span(&environment_string, position),
+                                      input: environment_string,
+                                      reason }
+}
+
+/// Parsing a target description written as a TOML document, e.g. `platform_machine = "aarch64"`
+///
+/// Args:
+/// - `environment_string` (String): A TOML document with a string for every marker variable except `extra` at its
+///   top level, other keys and tables are ignored
+///
+/// Returns `Result<MarkerEnvironment, Error>`: `Error::InvalidMarkerEnvironment` for syntax errors as well as repeated,
+/// missing or non-string variables
+#[cfg(feature = "environment")]
+pub fn parse_marker_environment_toml(environment_string: String) -> Result<MarkerEnvironment, Error> {
+    toml::from_str(&environment_string).map_err(|error| {
+                                           let span = error.span().unwrap_or(0..0);
+                                           Error::InvalidMarkerEnvironment { input: environment_string,
+                                                                             span,
+                                                                             reason: error.message().to_string() }
+                                       })
+}
@@ -0,0 +1,125 @@
+use std::ops::Range;
+use std::slice;
+
+use crate::Error;
+
+/// A single `Name: value` field of a header block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// The field name as it was written, e.g. `Requires-Dist`
+    pub name: String,
+    /// The value as it was written: continuation lines keep their line breaks and leading whitespace, as Python's
+    /// `email` package reads them, so that the value is exactly the text `value_span` points to
+    pub value: String,
+    /// The span of the whole field in the source, from the start of its name to the end of its last continuation line
+    pub span: Range<usize>,
+    /// The span of the value in the source, from the first character after `:` and any blanks to the end of the field
+    pub value_span: Range<usize>,
+}
+
+/// The RFC 822 style header block of a METADATA or PKG-INFO file, as an ordered multimap
+///
+/// Fields keep the order they were written in, may be repeated (e.g. `Classifier`) and are looked up ignoring ASCII
+/// case. Everything after the first empty line is the message body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Headers {
+    source: String,
+    headers: Vec<Header>,
+    body: Option<Range<usize>>,
+}
+
+impl Headers {
+    /// The document the headers were parsed from, which every span points into
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The first field called `name`, e.g. `"version"` for `Version: 1.0`
+    pub fn get(&self, name: &str) -> Option<&Header> {
+        self.headers.iter().find(|header| header.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Every field called `name`, in the order they were written
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Header> + 'a {
+        self.headers
+            .iter()
+            .filter(move |header| header.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Every field in the order they were written
+    pub fn iter(&self) -> slice::Iter<'_, Header> {
+        self.headers.iter()
+    }
+
+    /// The text after the empty line ending the header block, if there is one
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_ref().map(|span| &self.source[span.clone()])
+    }
+}
+
+impl<'a> IntoIterator for &'a Headers {
+    type Item = &'a Header;
+    type IntoIter = slice::Iter<'a, Header>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Field names may contain any printable ASCII character except `:` and space
+fn is_field_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|byte| matches!(byte, b'!'..=b'~') && byte != b':')
+}
+
+/// Parsing an RFC 822 style header block, e.g. the contents of a METADATA file
+///
+/// Lines starting with a space or a tab continue the previous field. The first empty line ends the header block.
+///
+/// Args:
+/// - `source` (String): The whole document
+///
+/// Returns `Result<Headers, Error>`: `Error::MalformedHeader` with the span of the first line that is neither a field
+/// nor a continuation
+pub fn parse_headers(source: String) -> Result<Headers, Error> {
+    let mut headers: Vec<Header> = Vec::new();
+    let mut body = None;
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(['\r', '\n']);
+        let end = start + line.len();
+        if line.is_empty() {
+            body = Some(offset..source.len());
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            match headers.last_mut() {
+                Some(header) => {
+                    header.value.push_str(&source[header.value_span.end..end]);
+                    header.span.end = end;
+                    header.value_span.end = end;
+                }
+                None => {
+                    return Err(Error::MalformedHeader { input: source.clone(),
+                                                        span: start..end })
+                }
+            }
+            continue;
+        }
+        match line.split_once(':') {
+            Some((name, value)) if is_field_name(name) => {
+                let value = value.trim_start_matches([' ', '\t']);
+                headers.push(Header { name: name.to_string(),
+                                      value: value.to_string(),
+                                      span: start..end,
+                                      value_span: end - value.len()..end });
+            }
+            _ => {
+                return Err(Error::MalformedHeader { input: source.clone(),
+                                                    span: start..end })
+            }
+        }
+    }
+    Ok(Headers { source, headers, body })
+}
@@ -1,90 +1,661 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::str::FromStr;
+
 use either::Either;
-use lazy_static::lazy_static;
-use regex::Regex;
+use header::{parse_headers, Header, Headers};
+use name::{parse_extra_name, parse_package_name, ExtraName, PackageName};
+use requirement::{parse_requirement, Requirement};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use smallvec::SmallVec;
+use specifier::{parse_specifier_set, SpecifierSet};
+
+pub mod bump;
+pub mod environment;
+pub mod header;
+pub mod marker;
+pub mod name;
+pub mod range;
+pub mod requirement;
+pub mod specifier;
+pub mod validate;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub enum PreReleaseType {
     Alpha,
     Beta,
     Rc,
 }
 
+impl fmt::Display for PreReleaseType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreReleaseType::Alpha => write!(f, "a"),
+            PreReleaseType::Beta => write!(f, "b"),
+            PreReleaseType::Rc => write!(f, "rc"),
+        }
+    }
+}
+
+/// A local version label, e.g. `ubuntu-1.cu118` in `1.0+ubuntu-1.cu118`
+///
+/// Labels compare segment by segment: numeric segments as numbers, alphanumeric ones case-insensitively as strings,
+/// numeric segments sort after alphanumeric ones and a label sorts after any label that is its prefix.
+#[derive(Debug, Clone)]
 pub struct LocalVersion {
-    pub parsed: Vec<Either<String, u32>>,
+    pub parsed: Vec<Either<String, u64>>,
     pub local_version_string: String,
 }
 
-#[derive(Debug)]
+impl PartialEq for LocalVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.parsed == other.parsed
+    }
+}
+
+impl Eq for LocalVersion {}
+
+impl Hash for LocalVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.parsed.hash(state);
+    }
+}
+
+impl PartialOrd for LocalVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LocalVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parsed.cmp(&other.parsed)
+    }
+}
+
+/// Writing the normalized label, segments separated by `.` and without leading zeros
+impl fmt::Display for LocalVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let segments = self.parsed
+                           .iter()
+                           .map(|segment| {
+                               segment.as_ref()
+                                      .either(|alpha| alpha.to_string(), |numeric| numeric.to_string())
+                           })
+                           .collect::<Vec<String>>();
+        write!(f, "{}", segments.join("."))
+    }
+}
+
+/// Release segments of a version, stored inline for up to four segments
+pub type Release = SmallVec<[u64; 4]>;
+
+#[derive(Debug, Clone)]
 pub struct Version {
-    pub epoch: Option<u32>,
-    pub release: Vec<u32>,
-    pub pre: Option<(PreReleaseType, Vec<u32>)>,
-    pub post: Option<u32>,
-    pub dev: Option<u32>,
-    pub local: Option<String>,
+    pub epoch: Option<u64>,
+    pub release: Release,
+    pub pre: Option<(PreReleaseType, u64)>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+    pub local: Option<LocalVersion>,
     pub version_string: String,
 }
 
+impl Version {
+    /// Getting the PEP 440 normalized form of the version, e.g. `1.0-ALPHA.1` becomes `1.0a1`
+    ///
+    /// Parsing the result with `parse_version` gives back a version equal to `self`.
+    pub fn to_canonical_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Whether this is a pre-release or a developmental release, e.g. `1.0a1` or `1.0.dev0`
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    /// Whether this is a developmental release, e.g. `1.0.dev0` or `1.0.post1.dev0`
+    pub fn is_devrelease(&self) -> bool {
+        self.dev.is_some()
+    }
+
+    /// Whether this is a post-release, e.g. `1.0.post1`
+    pub fn is_postrelease(&self) -> bool {
+        self.post.is_some()
+    }
+
+    /// The first release segment, `0` if missing
+    pub fn major(&self) -> u64 {
+        self.release.first().copied().unwrap_or(0)
+    }
+
+    /// The second release segment, `0` if missing
+    pub fn minor(&self) -> u64 {
+        self.release.get(1).copied().unwrap_or(0)
+    }
+
+    /// The third release segment, `0` if missing
+    pub fn micro(&self) -> u64 {
+        self.release.get(2).copied().unwrap_or(0)
+    }
+
+    /// Getting the epoch and release segments only, e.g. `1!2.0` for `1!2.0rc1.post2.dev3+local`
+    pub fn base_version(&self) -> Version {
+        let mut base = Version { epoch: self.epoch,
+                                 release: self.release.clone(),
+                                 pre: None,
+                                 post: None,
+                                 dev: None,
+                                 local: None,
+                                 version_string: String::new() };
+        base.version_string = base.to_string();
+        base
+    }
+
+    /// Getting the version without its local label, e.g. `1.0.post1` for `1.0.post1+ubuntu1`
+    pub fn public(&self) -> Version {
+        let mut public = Version { local: None,
+                                   ..self.clone() };
+        public.version_string = public.to_string();
+        public
+    }
+}
+
+/// Writing the PEP 440 normalized form of the version
+///
+/// https://peps.python.org/pep-0440/#normalization
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.epoch {
+            Some(epoch) if epoch != 0 => write!(f, "{}!", epoch)?,
+            _ => {}
+        }
+        for (index, n) in self.release.iter().enumerate() {
+            if index > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", n)?;
+        }
+        if let Some((pre_type, pre_n)) = &self.pre {
+            write!(f, "{}{}", pre_type, pre_n)?;
+        }
+        if let Some(post) = self.post {
+            write!(f, ".post{}", post)?;
+        }
+        if let Some(dev) = self.dev {
+            write!(f, ".dev{}", dev)?;
+        }
+        if let Some(local) = &self.local {
+            write!(f, "+{}", local)?;
+        }
+        Ok(())
+    }
+}
+
+/// Position of a version's pre-release part in the PEP 440 ordering
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum PreReleaseKey<'a> {
+    /// A developmental release of a final release, e.g. `1.0.dev0` which sorts before `1.0a0`
+    DevOnly,
+    PreRelease(&'a PreReleaseType, u64),
+    /// A final or post-release, sorting after all pre-releases of the same release
+    Final,
+}
+
+type VersionKey<'a> = (u64, &'a [u64], PreReleaseKey<'a>, Option<u64>, (bool, Option<u64>), Option<&'a LocalVersion>);
+
+impl Version {
+    /// Building the key versions are ordered by, following `packaging`'s `_cmpkey`
+    ///
+    /// https://peps.python.org/pep-0440/#summary-of-permitted-suffixes-and-relative-ordering
+    fn key(&self) -> VersionKey<'_> {
+        let mut release = self.release.as_slice();
+        while let [rest @ .., 0] = release {
+            release = rest;
+        }
+        let pre = match (&self.pre, self.post, self.dev) {
+            (Some((pre_type, pre_n)), _, _) => PreReleaseKey::PreRelease(pre_type, *pre_n),
+            (None, None, Some(_)) => PreReleaseKey::DevOnly,
+            (None, _, _) => PreReleaseKey::Final,
+        };
+        (self.epoch.unwrap_or(0), release, pre, self.post, (self.dev.is_none(), self.dev), self.local.as_ref())
+    }
+
+    /// Packing the key of common versions into a single integer, so that comparing them needs no branching on their parts,
+    /// see `sort_versions`
+    ///
+    /// Only versions without epoch and local label, with at most four release segments below 2^20 and pre-, post- and
+    /// dev-release numbers below 2^10 can be packed. From the most significant bits on: the four release segments,
+    /// the pre-release position (dev-only, pre-release, final), type and number, whether there is a post-release and its
+    /// number, whether there is *no* dev-release and its number.
+    fn packed_key(&self) -> Option<u128> {
+        if self.epoch.unwrap_or(0) != 0 || self.local.is_some() || self.release.len() > 4 {
+            return None;
+        }
+        let mut packed: u128 = 0;
+        for index in 0..4 {
+            let n = self.release.get(index).copied().unwrap_or(0);
+            if n >= 1 << 20 {
+                return None;
+            }
+            packed = packed << 20 | n as u128;
+        }
+        let (position, pre_type, pre_n) = match (&self.pre, self.post, self.dev) {
+            (Some((pre_type, pre_n)), _, _) => (1, pre_type.clone() as u128, *pre_n),
+            (None, None, Some(_)) => (0, 0, 0),
+            (None, _, _) => (2, 0, 0),
+        };
+        let (has_post, post) = self.post.map_or((0, 0), |post| (1, post));
+        let (no_dev, dev) = self.dev.map_or((1, 0), |dev| (0, dev));
+        if pre_n >= 1 << 10 || post >= 1 << 10 || dev >= 1 << 10 {
+            return None;
+        }
+        packed = packed << 2 | position;
+        packed = packed << 2 | pre_type;
+        packed = packed << 10 | pre_n as u128;
+        packed = packed << 1 | has_post;
+        packed = packed << 10 | post as u128;
+        packed = packed << 1 | no_dev;
+        packed = packed << 10 | dev as u128;
+        Some(packed)
+    }
+}
+
 impl PartialEq for Version {
     fn eq(&self, other: &Self) -> bool {
-        self.epoch == other.epoch
-        && self.release == other.release
-        && self.pre == other.pre
-        && self.post == other.post
-        && self.dev == other.dev
-        && self.local == other.local
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
     }
 }
 
 impl PartialOrd for Version {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.epoch != other.epoch {
-            return Some(self.epoch.cmp(&other.epoch));
-        }
-        if self.release != other.release {
-            return Some(self.release.cmp(&other.release));
-        }
-        if self.pre != other.pre {
-            match (self.pre.clone(), other.pre.clone()) {
-                (Some(_), None) => return Some(std::cmp::Ordering::Greater),
-                (None, Some(_)) => return Some(std::cmp::Ordering::Less),
-                (None, None) => return Some(std::cmp::Ordering::Equal),
-                (Some((this_pre, this_version)), Some((other_pre, other_version))) => {
-                    if this_pre != other_pre {
-                        return Some(this_pre.cmp(&other_pre));
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Sorting versions from oldest to newest, the same way `sort` does but faster for long lists of common versions
+///
+/// When every version can be packed into a single integer key (no epoch or local label, at most four release
+/// segments, small numbers), the keys are computed once up front instead of on every comparison.
+pub fn sort_versions(versions: &mut [Version]) {
+    if versions.iter().all(|version| version.packed_key().is_some()) {
+        versions.sort_by_cached_key(|version| version.packed_key());
+    }
+    else {
+        versions.sort();
+    }
+}
+
+/// A version string that does not conform to PEP 440, e.g. `2004d` or `1.0-SNAPSHOT`
+///
+/// Legacy versions are ordered among themselves the way `setuptools` used to order them and always sort before PEP 440
+/// versions, see `AnyVersion`.
+#[derive(Debug, Clone)]
+pub struct LegacyVersion {
+    pub version_string: String,
+    parts: Vec<String>,
+}
+
+impl LegacyVersion {
+    /// Building the legacy comparison key, following `packaging`'s `_legacy_cmpkey`
+    ///
+    /// The lowercased string is split into runs of digits, runs of letters and `.`/`-` separators. Numbers are zero
+    /// padded so that they compare numerically, `*final` marks the end of the version and trailing zeros as well as
+    /// `final-` markers before pre-release tags are dropped.
+    fn parts(version_string: &str) -> Vec<String> {
+        let lowercase = version_string.trim().to_lowercase();
+        let mut tokens = Vec::new();
+        let mut chars = lowercase.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let mut end = start + c.len_utf8();
+            if c != '.' && c != '-' {
+                let class = |c: char| (c.is_ascii_digit(), c.is_ascii_lowercase());
+                while let Some(&(next_start, next)) = chars.peek() {
+                    if next == '.' || next == '-' || class(next) != class(c) {
+                        break;
                     }
-                    return Some(this_version.cmp(&other_version));
+                    end = next_start + next.len_utf8();
+                    chars.next();
                 }
             }
+            tokens.push(&lowercase[start..end]);
         }
-        if self.post != other.post {
-            return Some(self.post.cmp(&other.post));
+
+        let tokens = tokens.into_iter().filter_map(|token| {
+                                           let part = match token {
+                                               "pre" | "preview" | "rc" => "c",
+                                               "-" => "final-",
+                                               "dev" => "@",
+                                               "." => return None,
+                                               token => token,
+                                           };
+                                           if part.starts_with(|c: char| c.is_ascii_digit()) {
+                                               Some(format!("{:0>8}", part))
+                                           }
+                                           else {
+                                               Some(format!("*{}", part))
+                                           }
+                                       });
+
+        let mut parts: Vec<String> = Vec::new();
+        for part in tokens.chain(std::iter::once("*final".to_string())) {
+            if part.starts_with('*') {
+                if part.as_str() < "*final" {
+                    while parts.last().map(String::as_str) == Some("*final-") {
+                        parts.pop();
+                    }
+                }
+                while parts.last().map(String::as_str) == Some("00000000") {
+                    parts.pop();
+                }
+            }
+            parts.push(part);
         }
-        if self.dev != other.dev {
-            return Some(self.dev.cmp(&other.dev));
+        parts
+    }
+}
+
+/// Parsing a version string that is not necessarily PEP 440 conformant
+///
+/// Args:
+/// - `version_string` (String): Any version string
+///
+/// Returns `LegacyVersion`: A LegacyVersion object, ordered like `setuptools` used to order versions
+pub fn parse_legacy_version(version_string: String) -> LegacyVersion {
+    LegacyVersion { parts: LegacyVersion::parts(&version_string),
+                    version_string }
+}
+
+impl PartialEq for LegacyVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.parts == other.parts
+    }
+}
+
+impl Eq for LegacyVersion {}
+
+impl Hash for LegacyVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.parts.hash(state);
+    }
+}
+
+impl PartialOrd for LegacyVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LegacyVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parts.cmp(&other.parts)
+    }
+}
+
+impl fmt::Display for LegacyVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.version_string.trim())
+    }
+}
+
+/// Either a PEP 440 version or, for old packages, a non-conformant legacy one
+///
+/// Legacy versions sort before all PEP 440 versions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AnyVersion {
+    Legacy(LegacyVersion),
+    Pep440(Version),
+}
+
+impl AnyVersion {
+    /// Getting the PEP 440 version, if this is one
+    pub fn as_pep440(&self) -> Option<&Version> {
+        match self {
+            AnyVersion::Pep440(version) => Some(version),
+            AnyVersion::Legacy(_) => None,
         }
-        if self.local != other.local {
-            return Some(self.local.cmp(&other.local));
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        matches!(self, AnyVersion::Legacy(_))
+    }
+}
+
+impl From<Version> for AnyVersion {
+    fn from(version: Version) -> Self {
+        AnyVersion::Pep440(version)
+    }
+}
+
+impl From<LegacyVersion> for AnyVersion {
+    fn from(version: LegacyVersion) -> Self {
+        AnyVersion::Legacy(version)
+    }
+}
+
+impl fmt::Display for AnyVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnyVersion::Legacy(version) => version.fmt(f),
+            AnyVersion::Pep440(version) => version.fmt(f),
         }
-        return None;
     }
 }
 
-type Operator = String;
-type Extra = String;
-type VersionConstraint = (Operator, Version);
-type Requirement = (String, Vec<VersionConstraint>, Extra);
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(version_string: &str) -> Result<Self, Self::Err> {
+        parse_version(version_string.to_string())
+    }
+}
+
+impl TryFrom<&str> for Version {
+    type Error = Error;
+
+    fn try_from(version_string: &str) -> Result<Self, Self::Error> {
+        parse_version(version_string.to_string())
+    }
+}
+
+/// Serializing as the normalized version string
+#[cfg(feature = "serde")]
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
 
-type Error = String;
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version_string = String::deserialize(deserializer)?;
+        parse_version(version_string).map_err(de::Error::custom)
+    }
+}
 
-#[derive(Debug)]
+/// Serializing as the normalized version string, or the original one for legacy versions
+#[cfg(feature = "serde")]
+impl Serialize for AnyVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for AnyVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(parse_any_version(String::deserialize(deserializer)?))
+    }
+}
+
+/// Errors produced while parsing versions and core metadata
+///
+/// Every `span` is a byte range into the `input` the failing function was given, i.e. the version string for
+/// `parse_version` and the whole metadata document for `parse_headers`, `get_metadata_attribute` and `parse_metadata`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input does not match the PEP 440 version grammar
+    InvalidVersion { input: String, span: Range<usize> },
+    /// A numeric version segment does not fit into its integer type
+    NumericOverflow { input: String, span: Range<usize> },
+    /// A required metadata field is not present
+    MissingMetadataField { field: String },
+    /// A metadata header line is not of the form `Key: value`
+    MalformedHeader { input: String, span: Range<usize> },
+    /// `Metadata-Version` has a major version this crate does not know how to read
+    UnsupportedMetadataVersion { input: String, span: Range<usize> },
+    /// A version cannot be bumped the requested way, e.g. from a release candidate back to an alpha
+    InvalidBump { version: String, reason: String },
+    /// A version specifier has no known operator or a version the operator does not allow, e.g. `~=1`
+    InvalidSpecifier {
+        input: String,
+        span: Range<usize>,
+        reason: String,
+    },
+    /// A dependency specification does not match the PEP 508 grammar
+    InvalidRequirement {
+        input: String,
+        span: Range<usize>,
+        reason: String,
+    },
+    /// An environment marker does not match the PEP 508 grammar
+    InvalidMarker {
+        input: String,
+        span: Range<usize>,
+        reason: String,
+    },
+    /// A package or extra name does not match the PEP 508 name grammar
+    InvalidName {
+        input: String,
+        span: Range<usize>,
+        reason: String,
+    },
+    /// A target description is not a flat JSON object or TOML document of the marker variables
+    InvalidMarkerEnvironment {
+        input: String,
+        span: Range<usize>,
+        reason: String,
+    },
+    /// A file could not be read
+    UnreadableFile { path: String, reason: String },
+    /// A Python interpreter could not be run or did not describe its environment
+    InterpreterFailed { interpreter: String, reason: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidVersion { input, .. } => write!(f, "{} is not a valid version", input),
+            Error::NumericOverflow { input, span } => {
+                write!(f, "{} in {} is too large to be a version number", &input[span.clone()], input)
+            }
+            Error::MissingMetadataField { field } => write!(f, "{} not found", field),
+            Error::MalformedHeader { input, span } => write!(f, "{} is not a valid metadata header", &input[span.clone()]),
+            Error::UnsupportedMetadataVersion { input, span } => {
+                write!(f, "Metadata-Version {} is not supported", &input[span.clone()])
+            }
+            Error::InvalidBump { version, reason } => write!(f, "cannot bump {}: {}", version, reason),
+            Error::InvalidSpecifier { input, reason, .. } => write!(f, "{} is not a valid version specifier: {}", input, reason),
+            Error::InvalidRequirement { input, reason, .. } => write!(f, "{} is not a valid requirement: {}", input, reason),
+            Error::InvalidMarker { input, reason, .. } => write!(f, "{} is not a valid marker: {}", input, reason),
+            Error::InvalidName { input, reason, .. } => write!(f, "{} is not a valid name: {}", input, reason),
+            Error::InvalidMarkerEnvironment { reason, .. } => write!(f, "invalid marker environment: {}", reason),
+            Error::UnreadableFile { path, reason } => write!(f, "cannot read {}: {}", path, reason),
+            Error::InterpreterFailed { interpreter, reason } => write!(f, "cannot query {}: {}", interpreter, reason),
+        }
+    }
+}
+
+impl Error {
+    /// The span of the error in its input, for the errors that have one
+    pub fn span(&self) -> Option<&Range<usize>> {
+        match self {
+            Error::InvalidVersion { span, .. }
+            | Error::NumericOverflow { span, .. }
+            | Error::MalformedHeader { span, .. }
+            | Error::UnsupportedMetadataVersion { span, .. }
+            | Error::InvalidSpecifier { span, .. }
+            | Error::InvalidRequirement { span, .. }
+            | Error::InvalidMarker { span, .. }
+            | Error::InvalidName { span, .. }
+            | Error::InvalidMarkerEnvironment { span, .. } => Some(span),
+            Error::MissingMetadataField { .. }
+            | Error::InvalidBump { .. }
+            | Error::UnreadableFile { .. }
+            | Error::InterpreterFailed { .. } => None,
+        }
+    }
+
+    /// Moving the span of an error about the part of `input` starting at `offset` to `input` as a whole
+    pub(crate) fn within(self, input: &str, offset: usize) -> Error {
+        let shift = |span: Range<usize>| offset + span.start..offset + span.end;
+        match self {
+            Error::InvalidVersion { span, .. } => Error::InvalidVersion { input: input.to_string(),
+                                                                          span: shift(span) },
+            Error::NumericOverflow { span, .. } => Error::NumericOverflow { input: input.to_string(),
+                                                                            span: shift(span) },
+            Error::MalformedHeader { span, .. } => Error::MalformedHeader { input: input.to_string(),
+                                                                            span: shift(span) },
+            Error::UnsupportedMetadataVersion { span, .. } => Error::UnsupportedMetadataVersion { input: input.to_string(),
+                                                                                                  span: shift(span) },
+            Error::InvalidSpecifier { span, reason, .. } => Error::InvalidSpecifier { input: input.to_string(),
+                                                                                      span: shift(span),
+                                                                                      reason },
+            Error::InvalidRequirement { span, reason, .. } => Error::InvalidRequirement { input: input.to_string(),
+                                                                                          span: shift(span),
+                                                                                          reason },
+            Error::InvalidMarker { span, reason, .. } => Error::InvalidMarker { input: input.to_string(),
+                                                                                span: shift(span),
+                                                                                reason },
+            Error::InvalidName { span, reason, .. } => Error::InvalidName { input: input.to_string(),
+                                                                            span: shift(span),
+                                                                            reason },
+            error => error,
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A `Project-URL` field, e.g. `Bug Tracker, https://github.com/pypa/pip/issues`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProjectUrl {
+    pub label: String,
+    pub url: String,
+}
+
+/// The core metadata of a distribution, as found in METADATA and PKG-INFO files
+///
+/// Fields that may be used more than once hold every value in the order they were written, and fields that are not
+/// present are `None`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Metadata {
     pub metadata_version: Version,
-    pub name: String,
-    pub version: Version,
-    pub dynamic: Option<String>,
+    pub name: PackageName,
+    pub version: AnyVersion,
+    pub dynamic: Option<Vec<String>>,
     pub platforms: Option<Vec<String>>,
-    pub suported_platforms: Option<Vec<String>>,
+    pub supported_platforms: Option<Vec<String>>,
     pub summary: Option<String>,
     pub description: Option<String>,
     pub description_content_type: Option<String>,
@@ -98,10 +669,304 @@ pub struct Metadata {
     pub license: Option<String>,
     pub classifiers: Option<Vec<String>>,
     pub requires_dist: Option<Vec<Requirement>>,
-    pub requires_python: Option<Vec<VersionConstraint>>,
+    pub requires_python: Option<SpecifierSet>,
     pub requires_externals: Option<Vec<String>>,
-    pub project_urls: Option<Vec<String>>,
-    pub provides_extras: Option<Vec<Extra>>,
+    pub project_urls: Option<Vec<ProjectUrl>>,
+    pub provides_extras: Option<Vec<ExtraName>>,
+    /// The errors of `Requires-Dist`, `Requires-Python`, `Project-URL` and `Provides-Extra` fields that could not be
+    /// parsed and were left out, with spans into the metadata document
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub invalid_fields: Vec<Error>,
+}
+
+/// Writing a `field: value` line, with every line break in `value` folded into a continuation line starting with
+/// `indent`
+fn write_field(f: &mut fmt::Formatter<'_>, field: &str, value: &str, indent: &str) -> fmt::Result {
+    let mut lines = value.split('\n').map(|line| line.trim_end_matches('\r'));
+    writeln!(f, "{}: {}", field, lines.next().unwrap_or_default())?;
+    for line in lines {
+        writeln!(f, "{}{}", indent, line)?;
+    }
+    Ok(())
+}
+
+/// Joining keywords with commas, with a trailing comma if a single keyword contains whitespace, because `Keywords`
+/// without a comma are split on whitespace
+fn join_keywords(keywords: &[String]) -> String {
+    let joined = keywords.join(",");
+    if keywords.len() == 1 && joined.contains(char::is_whitespace) {
+        joined + ","
+    }
+    else {
+        joined
+    }
+}
+
+/// Every field of `metadata` except `Metadata-Version`, once per value and in the order of the core metadata
+/// specification
+fn metadata_fields(metadata: &Metadata) -> Vec<(&'static str, String)> {
+    let single = |value: &Option<String>| value.iter().cloned().collect::<Vec<String>>();
+    let multiple = |values: &Option<Vec<String>>| values.iter().flatten().cloned().collect::<Vec<String>>();
+    let fields = [("Name", vec![metadata.name.to_string()]),
+                  ("Version", vec![metadata.version.to_string()]),
+                  ("Dynamic", multiple(&metadata.dynamic)),
+                  ("Platform", multiple(&metadata.platforms)),
+                  ("Supported-Platform", multiple(&metadata.supported_platforms)),
+                  ("Summary", single(&metadata.summary)),
+                  ("Description", single(&metadata.description)),
+                  ("Description-Content-Type", single(&metadata.description_content_type)),
+                  ("Keywords", metadata.keywords.iter().map(|keywords| join_keywords(keywords)).collect()),
+                  ("Home-page", single(&metadata.homepage)),
+                  ("Download-URL", single(&metadata.download_url)),
+                  ("Author", single(&metadata.author)),
+                  ("Author-email", single(&metadata.author_email)),
+                  ("Maintainer", single(&metadata.maintainer)),
+                  ("Maintainer-email", single(&metadata.maintainer_email)),
+                  ("License", single(&metadata.license)),
+                  ("Classifier", multiple(&metadata.classifiers)),
+                  ("Requires-Dist", metadata.requires_dist.iter().flatten().map(Requirement::to_string).collect()),
+                  ("Requires-Python", metadata.requires_python.iter().map(SpecifierSet::to_string).collect()),
+                  ("Requires-External", multiple(&metadata.requires_externals)),
+                  ("Project-URL",
+                   metadata.project_urls
+                           .iter()
+                           .flatten()
+                           .map(|project_url| format!("{}, {}", project_url.label, project_url.url))
+                           .collect()),
+                  ("Provides-Extra", metadata.provides_extras.iter().flatten().map(ExtraName::to_string).collect())];
+    fields.into_iter()
+          .flat_map(|(field, values)| values.into_iter().map(move |value| (field, value)))
+          .collect()
+}
+
+/// Writing the metadata as a METADATA or PKG-INFO file that `parse_metadata` reads back
+///
+/// Fields are written in the order of the core metadata specification, once per value, and `Metadata-Version` is
+/// raised to the lowest version defining every field present. The description goes into the message body since
+/// Metadata-Version 2.1 and into a `Description` field folded the way setuptools does before. The fields in
+/// `invalid_fields` were left out when reading and are not written.
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = metadata_fields(self);
+
+        let declared = (self.metadata_version.release[0], self.metadata_version.release.get(1).copied().unwrap_or(0));
+        let required = fields.iter()
+                             .filter_map(|(field, _)| validate::introduced_in(field))
+                             .max()
+                             .unwrap_or((1, 0));
+        let version = if required > declared {
+            writeln!(f, "Metadata-Version: {}.{}", required.0, required.1)?;
+            required
+        }
+        else {
+            writeln!(f, "Metadata-Version: {}", self.metadata_version)?;
+            declared
+        };
+
+        let body = version >= (2, 1);
+        for (field, value) in &fields {
+            match *field {
+                "Description" if body => continue,
+                "Description" => write_field(f, field, value, "       |")?,
+                _ => write_field(f, field, value, "        ")?,
+            }
+        }
+        match &self.description {
+            Some(description) if body => write!(f, "\n{}", description),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A cursor over a version string, parsing it front to back without backtracking over numbers
+struct Scanner<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    /// The first number too large for `u64`, only reported once the whole string is known to be well-formed
+    overflow: Option<Range<usize>>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(input: &'a str) -> Self {
+        Scanner { input,
+                  bytes: input.as_bytes(),
+                  position: 0,
+                  overflow: None }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.position + offset).copied()
+    }
+
+    /// Consuming one of the `-`, `_` and `.` separators
+    fn separator(&mut self) -> bool {
+        match self.peek() {
+            Some(b'-' | b'_' | b'.') => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consuming the first of `labels` the input continues with, ignoring ASCII case
+    fn label(&mut self, labels: &[&str]) -> Option<usize> {
+        let rest = &self.bytes[self.position..];
+        let index =
+            labels.iter()
+                  .position(|label| rest.len() >= label.len() && rest[..label.len()].eq_ignore_ascii_case(label.as_bytes()))?;
+        self.position += labels[index].len();
+        Some(index)
+    }
+
+    /// Consuming a run of ASCII digits, remembering where the first overflowing number is
+    fn number(&mut self) -> Option<u64> {
+        let start = self.position;
+        let mut value: Option<u64> = Some(0);
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            value = value.and_then(|value| value.checked_mul(10))
+                         .and_then(|value| value.checked_add((digit - b'0') as u64));
+            self.position += 1;
+        }
+        if self.position == start {
+            return None;
+        }
+        if value.is_none() && self.overflow.is_none() {
+            self.overflow = Some(start..self.position);
+        }
+        Some(value.unwrap_or(u64::MAX))
+    }
+
+    /// Consuming `[-_.]? label [-_.]? number?` with `number` implicitly `0`, or nothing if no label follows
+    fn suffix(&mut self, labels: &[&str]) -> Option<(usize, u64)> {
+        let start = self.position;
+        self.separator();
+        match self.label(labels) {
+            Some(index) => {
+                self.separator();
+                Some((index, self.number().unwrap_or(0)))
+            }
+            None => {
+                self.position = start;
+                None
+            }
+        }
+    }
+
+    /// Consuming `[a-z0-9]+ ([-_.] [a-z0-9]+)*`, splitting it into numeric and lowercase alphanumeric segments
+    fn local(&mut self) -> Option<LocalVersion> {
+        let start = self.position;
+        let mut parsed = Vec::new();
+        loop {
+            let segment_start = self.position;
+            while self.peek().is_some_and(|byte| byte.is_ascii_alphanumeric()) {
+                self.position += 1;
+            }
+            let segment = &self.input[segment_start..self.position];
+            if segment.is_empty() {
+                return None;
+            }
+            if segment.bytes().all(|byte| byte.is_ascii_digit()) {
+                self.position = segment_start;
+                parsed.push(Either::Right(self.number()?));
+            }
+            else {
+                parsed.push(Either::Left(segment.to_ascii_lowercase()));
+            }
+            match (self.peek(), self.peek_at(1)) {
+                (Some(b'-' | b'_' | b'.'), Some(next)) if next.is_ascii_alphanumeric() => self.position += 1,
+                _ => break,
+            }
+        }
+        Some(LocalVersion { parsed,
+                            local_version_string: self.input[start..self.position].to_string() })
+    }
+
+    /// Consuming a whole version, following the regex in https://peps.python.org/pep-0440/#appendix-b-parsing-version-strings-with-regular-expressions
+    ///
+    /// Only local version labels allocate, `version_string` is left empty for the caller to fill in.
+    fn version(&mut self) -> Option<Version> {
+        self.position = self.input.len() - self.input.trim_start().len();
+        self.label(&["v"]);
+
+        let mut epoch = None;
+        let mut release = Release::new();
+        release.push(self.number()?);
+        if self.peek() == Some(b'!') {
+            self.position += 1;
+            epoch = release.pop();
+            release.push(self.number()?);
+        }
+        while self.peek() == Some(b'.') && self.peek_at(1).is_some_and(|byte| byte.is_ascii_digit()) {
+            self.position += 1;
+            release.push(self.number()?);
+        }
+
+        let pre = self.suffix(&["preview", "alpha", "beta", "pre", "rc", "a", "b", "c"])
+                      .map(|(index, pre_n)| match index {
+                          1 | 5 => (PreReleaseType::Alpha, pre_n),
+                          2 | 6 => (PreReleaseType::Beta, pre_n),
+                          _ => (PreReleaseType::Rc, pre_n),
+                      });
+
+        let post = match (self.peek(), self.peek_at(1)) {
+            (Some(b'-'), Some(b'0'..=b'9')) => {
+                self.position += 1;
+                self.number()
+            }
+            _ => self.suffix(&["post", "rev", "r"]).map(|(_, post)| post),
+        };
+
+        let dev = self.suffix(&["dev"]).map(|(_, dev)| dev);
+
+        let local = match self.peek() {
+            Some(b'+') => {
+                self.position += 1;
+                Some(self.local()?)
+            }
+            _ => None,
+        };
+
+        if !self.input[self.position..].chars().all(char::is_whitespace) {
+            return None;
+        }
+        Some(Version { epoch,
+                       release,
+                       pre,
+                       post,
+                       dev,
+                       local,
+                       version_string: String::new() })
+    }
+}
+
+/// Parsing a PEP 440 local version label, i.e. the part of a version after `+`
+///
+/// https://peps.python.org/pep-0440/#local-version-identifiers
+///
+/// Args:
+/// - `local_version_string` (String): A local version label without the leading `+`
+///
+/// Returns `Result<LocalVersion, Error>`: A LocalVersion object
+pub fn parse_local_version(local_version_string: String) -> Result<LocalVersion, Error> {
+    let mut scanner = Scanner::new(&local_version_string);
+    let local = match scanner.local() {
+        Some(local) if scanner.position == local_version_string.len() => local,
+        _ => {
+            return Err(Error::InvalidVersion { span: 0..local_version_string.len(),
+                                               input: local_version_string })
+        }
+    };
+    match scanner.overflow {
+        Some(span) => Err(Error::NumericOverflow { input: local_version_string,
+                                                   span }),
+        None => Ok(local),
+    }
 }
 
 /// Parsing PEP 440 conformant version strings
@@ -111,97 +976,244 @@ pub struct Metadata {
 /// Args:
 /// - `version_string` (String): A version string
 ///
-/// Returns `Result<Version, Error>`: A Version object
+/// Returns `Result<Version, Error>`: A Version object, or `Error::InvalidVersion`/`Error::NumericOverflow`
 pub fn parse_version(version_string: String) -> Result<Version, Error> {
-    // TODO: This function has been largely written by Copilot and needs to be cleaned up
-    lazy_static! {
-        static ref RE: Regex = Regex::new(
-            r"^\s*v?(?:(?:(?P<epoch>[0-9]+)!)?(?P<release>[0-9]+(?:\.[0-9]+)*)(?P<pre>[-_\.]?(?P<pre_l>(a|b|c|rc|alpha|beta|pre|preview))[-_\.]?(?P<pre_n>[0-9]+)?)?(?P<post>(?:-(?P<post_n1>[0-9]+))|(?:[-_\.]?(?P<post_l>post|rev|r)[-_\.]?(?P<post_n2>[0-9]+)?))?(?P<dev>[-_\.]?(?P<dev_l>dev)[-_\.]?(?P<dev_n>[0-9]+)?)?)(?:\+(?P<local>[a-z0-9]+(?:[-_\.][a-z0-9]+)*))?\s*$"
-        ).unwrap();
-    }
-
-    let captures = RE.captures(&version_string)
-                     .ok_or(format!("{} is not a valid version", version_string))?;
-
-    let epoch = captures.name("epoch").map(|epoch| epoch.as_str().parse::<u32>().unwrap());
-
-    let release = captures.name("release").map(|release| {
-                                              release.as_str()
-                                                     .split(".")
-                                                     .map(|n| n.parse::<u32>().unwrap())
-                                                     .collect::<Vec<u32>>()
-                                          });
-
-    let pre: Option<(PreReleaseType, Vec<u32>)>;
-
-    if captures.name("pre").is_some() {
-        let pre_l = captures.name("pre_l").map(|pre_l| pre_l.as_str()).unwrap();
-        let pre_n = captures.name("pre_n")
-                            .map(|pre_n| pre_n.as_str().parse::<u32>().unwrap())
-                            .unwrap();
-        pre = Some((match pre_l {
-                        "a" | "alpha" => PreReleaseType::Alpha,
-                        "b" | "beta" => PreReleaseType::Beta,
-                        "c" | "rc" | "pre" | "preview" => PreReleaseType::Rc,
-                        _ => unreachable!(),
-                    },
-                    vec![pre_n]));
+    let mut scanner = Scanner::new(&version_string);
+    let version = match scanner.version() {
+        Some(version) => version,
+        None => {
+            return Err(Error::InvalidVersion { span: 0..version_string.len(),
+                                               input: version_string })
+        }
+    };
+    if let Some(span) = scanner.overflow {
+        return Err(Error::NumericOverflow { input: version_string,
+                                            span });
+    }
+
+    Ok(Version { version_string,
+                 ..version })
+}
+
+/// Parsing a version string, falling back to a `LegacyVersion` if it is not PEP 440 conformant
+///
+/// Args:
+/// - `version_string` (String): Any version string
+///
+/// Returns `AnyVersion`: A PEP 440 Version if possible, a LegacyVersion otherwise
+pub fn parse_any_version(version_string: String) -> AnyVersion {
+    match parse_version(version_string.clone()) {
+        Ok(version) => AnyVersion::Pep440(version),
+        Err(_) => AnyVersion::Legacy(parse_legacy_version(version_string)),
+    }
+}
+
+/// Finding the first `field` of `headers`, rejecting fields that are missing or have no value
+fn required_field<'a>(headers: &'a Headers, field: &str) -> Result<&'a Header, Error> {
+    let header = match headers.get(field) {
+        Some(header) => header,
+        None => return Err(Error::MissingMetadataField { field: field.to_string() }),
+    };
+    if header.value.trim().is_empty() {
+        return Err(Error::MalformedHeader { input: headers.source().to_string(),
+                                            span: header.span.clone() });
+    }
+    Ok(header)
+}
+
+/// Getting the value of the first `attribute` field (e.g. `"Name:"` or `"name"`), ignoring case
+///
+/// Returns `Error::MissingMetadataField` if there is no such field and `Error::MalformedHeader` if the field has no
+/// value or the document is not a valid header block.
+pub fn get_metadata_attribute(metadata_string: &str, attribute: &str) -> Result<String, Error> {
+    let headers = parse_headers(metadata_string.to_string())?;
+    required_field(&headers, attribute.trim_end_matches(':')).map(|header| header.value.clone())
+}
+
+/// Removing the indentation that folding a value added to its continuation lines: 7 spaces and a `|` as setuptools
+/// writes descriptions, 8 spaces as `Metadata` writes every field, or any leading blanks otherwise
+fn unfold(value: &str) -> String {
+    let mut lines = value.split('\n').map(|line| line.trim_end_matches('\r'));
+    let mut unfolded = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        let line = match line.strip_prefix("       |").or_else(|| line.strip_prefix("        ")) {
+            Some(line) => line,
+            None => line.trim_start_matches([' ', '\t']),
+        };
+        unfolded.push('\n');
+        unfolded.push_str(line);
+    }
+    unfolded
+}
+
+/// Getting the unfolded value of the first `field` of `headers`, or `None` if it is missing or empty
+fn optional_field(headers: &Headers, field: &str) -> Option<String> {
+    headers.get(field)
+           .map(|header| unfold(&header.value).trim().to_string())
+           .filter(|value| !value.is_empty())
+}
+
+/// Getting the unfolded values of every non-empty `field` of `headers`, or `None` if there are none
+fn multiple_field(headers: &Headers, field: &str) -> Option<Vec<String>> {
+    let values = headers.get_all(field)
+                        .map(|header| unfold(&header.value).trim().to_string())
+                        .filter(|value| !value.is_empty())
+                        .collect::<Vec<String>>();
+    if values.is_empty() {
+        None
     }
     else {
-        pre = None;
+        Some(values)
     }
+}
 
-    let post = captures.name("post_n2").map(|post| post.as_str().parse::<u32>().unwrap());
-    let dev = captures.name("dev_n").map(|dev| dev.as_str().parse::<u32>().unwrap());
-    let local = captures.name("local").map(|local| local.as_str().to_string());
+/// Parsing the value of `header` with `parse`, moving error spans from the value to the whole metadata document
+fn parse_field<T>(headers: &Headers, header: &Header, parse: fn(String) -> Result<T, Error>) -> Result<T, Error> {
+    parse(header.value.clone()).map_err(|error| error.within(headers.source(), header.value_span.start))
+}
 
-    Ok(Version { epoch,
-                 release: release.unwrap(),
-                 pre: pre,
-                 post,
-                 dev,
-                 local,
-                 version_string })
+/// Parsing the value of every `field` of `headers` with `parse`, leaving out the values that fail and adding their
+/// errors to `invalid`
+fn parse_multiple_field<T>(headers: &Headers, field: &str, parse: fn(String) -> Result<T, Error>, invalid: &mut Vec<Error>)
+                           -> Option<Vec<T>> {
+    let mut values = Vec::new();
+    for header in headers.get_all(field) {
+        match parse_field(headers, header, parse) {
+            Ok(value) => values.push(value),
+            Err(error) => invalid.push(error),
+        }
+    }
+    if values.is_empty() {
+        None
+    }
+    else {
+        Some(values)
+    }
 }
 
-pub fn get_metadata_attribute(metadata_string: &String, attribute: String) -> Result<String, Error> {
-    let attribute = metadata_string.lines()
-                                   .find(|line| line.starts_with(&attribute))
-                                   .map(|line| line.split(": ").nth(1).unwrap().to_string())
-                                   .ok_or(format!("{} not found", attribute))?;
-    Ok(attribute)
+/// Splitting `Keywords`, which are separated by commas since Metadata-Version 1.2 and by whitespace before
+fn split_keywords(keywords: &str) -> Vec<String> {
+    let keywords: Vec<&str> = if keywords.contains(',') {
+        keywords.split(',').collect()
+    }
+    else {
+        keywords.split_whitespace().collect()
+    };
+    keywords.into_iter()
+            .map(str::trim)
+            .filter(|keyword| !keyword.is_empty())
+            .map(str::to_string)
+            .collect()
 }
 
+/// Getting the long description, from the message body since Metadata-Version 2.1 or from a legacy `Description`
+/// field otherwise
+///
+/// Legacy descriptions are folded with every continuation line indented by 8 spaces, or by 7 spaces and a `|` as
+/// setuptools writes them, which is stripped again by `unfold`.
+fn parse_description(headers: &Headers) -> Option<String> {
+    if let Some(body) = headers.body().filter(|body| !body.trim().is_empty()) {
+        return Some(body.to_string());
+    }
+    let header = headers.get("Description").filter(|header| !header.value.trim().is_empty())?;
+    Some(unfold(&header.value))
+}
+
+/// Parsing every `Project-URL` field, which must be of the form `label, url` with neither part empty, adding an
+/// `Error::MalformedHeader` to `invalid` for every field that is not
+fn parse_project_urls(headers: &Headers, invalid: &mut Vec<Error>) -> Option<Vec<ProjectUrl>> {
+    let mut project_urls = Vec::new();
+    for header in headers.get_all("Project-URL") {
+        match header.value.split_once(',') {
+            Some((label, url)) if !label.trim().is_empty() && !url.trim().is_empty() => {
+                project_urls.push(ProjectUrl { label: label.trim().to_string(),
+                                               url: url.trim().to_string() })
+            }
+            _ => invalid.push(Error::MalformedHeader { input: headers.source().to_string(),
+                                                       span: header.span.clone() }),
+        }
+    }
+    if project_urls.is_empty() {
+        None
+    }
+    else {
+        Some(project_urls)
+    }
+}
+
+/// Parsing a version field, moving error spans from the value to the whole metadata document
+fn parse_version_field(headers: &Headers, field: &str) -> Result<Version, Error> {
+    let header = required_field(headers, field)?;
+    parse_version(header.value.clone()).map_err(|error| error.within(headers.source(), header.value_span.start))
+}
+
+/// Parsing the contents of a METADATA or PKG-INFO file
+///
+/// Only `Metadata-Version`, `Name` and `Version` must be readable. A `Version` that is not PEP 440 conformant becomes a
+/// `LegacyVersion`, and `Requires-Dist`, `Requires-Python`, `Project-URL` and `Provides-Extra` fields that cannot be
+/// parsed, as old distributions often have, are left out and their errors collected in `Metadata::invalid_fields`.
+/// `validate::validate_metadata` reports them as violations.
+///
+/// Args:
+/// - `metadata_string` (String): The contents of a METADATA or PKG-INFO file
+///
+/// Returns `Result<Metadata, Error>`: The metadata, or `Error::MissingMetadataField`, `Error::MalformedHeader`,
+/// `Error::UnsupportedMetadataVersion` or the error of an unreadable `Metadata-Version` or `Name`
 pub fn parse_metadata(metadata_string: String) -> Result<Metadata, Error> {
-    let metadata_version = parse_version(get_metadata_attribute(&metadata_string, "Metadata-Version:".to_string())?).unwrap();
+    let headers = parse_headers(metadata_string)?;
+    let source = headers.source();
+
+    let metadata_version = parse_version_field(&headers, "Metadata-Version")?;
+    if !matches!(metadata_version.release[0], 1 | 2) {
+        return Err(Error::UnsupportedMetadataVersion { input: source.to_string(),
+                                                       span: required_field(&headers, "Metadata-Version")?.value_span
+                                                                                                          .clone() });
+    }
 
-    let name = get_metadata_attribute(&metadata_string, "Name:".to_string())?;
+    let name = required_field(&headers, "Name")?;
+    let name = parse_package_name(name.value.clone()).map_err(|error| error.within(source, name.value_span.start))?;
 
-    let version = parse_version(get_metadata_attribute(&metadata_string, "Version:".to_string())?).unwrap();
+    let version = parse_any_version(required_field(&headers, "Version")?.value.clone());
 
-    // FIXME: There is much more metadata to be parsed here but I am currently focusing on version parsing
+    let mut invalid_fields = Vec::new();
+    let requires_python = match headers.get("Requires-Python")
+                                       .filter(|header| !header.value.trim().is_empty())
+                                       .map(|header| parse_field(&headers, header, parse_specifier_set))
+    {
+        Some(Ok(requires_python)) => Some(requires_python),
+        Some(Err(error)) => {
+            invalid_fields.push(error);
+            None
+        }
+        None => None,
+    };
+    let requires_dist = parse_multiple_field(&headers, "Requires-Dist", parse_requirement, &mut invalid_fields);
+    let project_urls = parse_project_urls(&headers, &mut invalid_fields);
+    let provides_extras = parse_multiple_field(&headers, "Provides-Extra", parse_extra_name, &mut invalid_fields);
+    invalid_fields.sort_by_key(|error| error.span().map(|span| span.start));
 
     Ok(Metadata { metadata_version,
                   name,
                   version,
-                  dynamic: None,
-                  platforms: None,
-                  suported_platforms: None,
-                  summary: None,
-                  description: None,
-                  description_content_type: None,
-                  keywords: None,
-                  homepage: None,
-                  download_url: None,
-                  author: None,
-                  author_email: None,
-                  maintainer: None,
-                  maintainer_email: None,
-                  license: None,
-                  classifiers: None,
-                  requires_dist: None,
-                  requires_python: None,
-                  requires_externals: None,
-                  project_urls: None,
-                  provides_extras: None })
+                  dynamic: multiple_field(&headers, "Dynamic"),
+                  platforms: multiple_field(&headers, "Platform"),
+                  supported_platforms: multiple_field(&headers, "Supported-Platform"),
+                  summary: optional_field(&headers, "Summary"),
+                  description: parse_description(&headers),
+                  description_content_type: optional_field(&headers, "Description-Content-Type"),
+                  keywords: optional_field(&headers, "Keywords").map(|keywords| split_keywords(&keywords)),
+                  homepage: optional_field(&headers, "Home-page"),
+                  download_url: optional_field(&headers, "Download-URL"),
+                  author: optional_field(&headers, "Author"),
+                  author_email: optional_field(&headers, "Author-email"),
+                  maintainer: optional_field(&headers, "Maintainer"),
+                  maintainer_email: optional_field(&headers, "Maintainer-email"),
+                  license: optional_field(&headers, "License"),
+                  classifiers: multiple_field(&headers, "Classifier"),
+                  requires_dist,
+                  requires_python,
+                  requires_externals: multiple_field(&headers, "Requires-External"),
+                  project_urls,
+                  provides_extras,
+                  invalid_fields })
 }
@@ -0,0 +1,1101 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+use std::ops::Range;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::bump::normalized;
+use crate::name::normalize;
+use crate::range::{Segment, VersionRange};
+use crate::specifier::{parse_version_specifier, Operator, SpecifierSet, VersionSpecifier};
+use crate::{parse_version, Error, Version};
+
+/// A variable of the environment markers are evaluated in, see https://peps.python.org/pep-0508/#environment-markers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MarkerVariable {
+    ImplementationName,
+    ImplementationVersion,
+    OsName,
+    PlatformMachine,
+    PlatformPythonImplementation,
+    PlatformRelease,
+    PlatformSystem,
+    PlatformVersion,
+    PythonFullVersion,
+    PythonVersion,
+    SysPlatform,
+    /// The extra a requirement is evaluated for, only set while resolving the extras of a distribution
+    Extra,
+}
+
+impl MarkerVariable {
+    /// All variables by name, including the dotted spellings `packaging` still accepts
+    const ALL: [(&'static str, MarkerVariable); 18] =
+        [("implementation_name", MarkerVariable::ImplementationName),
+         ("implementation_version", MarkerVariable::ImplementationVersion),
+         ("os_name", MarkerVariable::OsName),
+         ("platform_machine", MarkerVariable::PlatformMachine),
+         ("platform_python_implementation", MarkerVariable::PlatformPythonImplementation),
+         ("platform_release", MarkerVariable::PlatformRelease),
+         ("platform_system", MarkerVariable::PlatformSystem),
+         ("platform_version", MarkerVariable::PlatformVersion),
+         ("python_full_version", MarkerVariable::PythonFullVersion),
+         ("python_version", MarkerVariable::PythonVersion),
+         ("sys_platform", MarkerVariable::SysPlatform),
+         ("extra", MarkerVariable::Extra),
+         ("os.name", MarkerVariable::OsName),
+         ("platform.machine", MarkerVariable::PlatformMachine),
+         ("platform.python_implementation", MarkerVariable::PlatformPythonImplementation),
+         ("platform.version", MarkerVariable::PlatformVersion),
+         ("python_implementation", MarkerVariable::PlatformPythonImplementation),
+         ("sys.platform", MarkerVariable::SysPlatform)];
+}
+
+impl fmt::Display for MarkerVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, _) = MarkerVariable::ALL.iter().find(|(_, variable)| variable == self).unwrap();
+        write!(f, "{}", name)
+    }
+}
+
+/// One side of a marker comparison
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MarkerValue {
+    Variable(MarkerVariable),
+    String(String),
+}
+
+/// Writing variables by name and strings in double quotes, or single quotes if they contain a double quote
+impl fmt::Display for MarkerValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkerValue::Variable(variable) => write!(f, "{}", variable),
+            MarkerValue::String(string) if string.contains('"') => write!(f, "'{}'", string),
+            MarkerValue::String(string) => write!(f, "\"{}\"", string),
+        }
+    }
+}
+
+/// A marker comparison operator: the version comparison operators, `in` and `not in`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarkerOperator {
+    Compare(Operator),
+    In,
+    NotIn,
+}
+
+impl fmt::Display for MarkerOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkerOperator::Compare(operator) => write!(f, "{}", operator),
+            MarkerOperator::In => write!(f, "in"),
+            MarkerOperator::NotIn => write!(f, "not in"),
+        }
+    }
+}
+
+/// A single comparison, e.g. `python_version >= "3.8"`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MarkerExpression {
+    pub left: MarkerValue,
+    pub operator: MarkerOperator,
+    pub right: MarkerValue,
+}
+
+impl fmt::Display for MarkerExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.left, self.operator, self.right)
+    }
+}
+
+/// An environment marker, e.g. `python_version >= "3.8" and (sys_platform == "linux" or extra == "test")`
+///
+/// `And` and `Or` never have a child of their own kind and have at least two children, except for the markers that
+/// always (`MarkerTree::TRUE`) and never (`MarkerTree::FALSE`) hold, which simplification can end up with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MarkerTree {
+    Expression(MarkerExpression),
+    And(Vec<MarkerTree>),
+    Or(Vec<MarkerTree>),
+}
+
+/// Writing the marker with single spaces and only the parentheses `or` inside `and` needs
+///
+/// PEP 508 has no syntax for `MarkerTree::TRUE` and `MarkerTree::FALSE`, they are written as the comparisons of two
+/// strings `"" == ""` and `"" != ""`, which parse back to markers that always and never hold.
+impl fmt::Display for MarkerTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkerTree::Expression(expression) => write!(f, "{}", expression),
+            MarkerTree::And(children) if children.is_empty() => write!(f, "\"\" == \"\""),
+            MarkerTree::Or(children) if children.is_empty() => write!(f, "\"\" != \"\""),
+            MarkerTree::And(children) => {
+                for (index, child) in children.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " and ")?;
+                    }
+                    match child {
+                        MarkerTree::Or(_) => write!(f, "({})", child)?,
+                        _ => write!(f, "{}", child)?,
+                    }
+                }
+                Ok(())
+            }
+            MarkerTree::Or(children) => {
+                for (index, child) in children.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " or ")?;
+                    }
+                    write!(f, "{}", child)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The values of the marker variables for one target interpreter and platform, e.g. CPython 3.11 on x86_64 Linux
+///
+/// `extra` is not part of the environment, it is given to `MarkerTree::evaluate` separately. An environment can
+/// describe the local interpreter, see `MarkerEnvironment::from_interpreter`, or with the `environment` feature a
+/// target `rip` does not run on, see `MarkerEnvironment::from_file`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MarkerEnvironment {
+    pub implementation_name: String,
+    pub implementation_version: String,
+    pub os_name: String,
+    pub platform_machine: String,
+    pub platform_python_implementation: String,
+    pub platform_release: String,
+    pub platform_system: String,
+    pub platform_version: String,
+    pub python_full_version: String,
+    pub python_version: String,
+    pub sys_platform: String,
+}
+
+impl MarkerEnvironment {
+    /// The value of `variable`, `None` for `extra`
+    pub fn get(&self, variable: MarkerVariable) -> Option<&str> {
+        let value = match variable {
+            MarkerVariable::ImplementationName => &self.implementation_name,
+            MarkerVariable::ImplementationVersion => &self.implementation_version,
+            MarkerVariable::OsName => &self.os_name,
+            MarkerVariable::PlatformMachine => &self.platform_machine,
+            MarkerVariable::PlatformPythonImplementation => &self.platform_python_implementation,
+            MarkerVariable::PlatformRelease => &self.platform_release,
+            MarkerVariable::PlatformSystem => &self.platform_system,
+            MarkerVariable::PlatformVersion => &self.platform_version,
+            MarkerVariable::PythonFullVersion => &self.python_full_version,
+            MarkerVariable::PythonVersion => &self.python_version,
+            MarkerVariable::SysPlatform => &self.sys_platform,
+            MarkerVariable::Extra => return None,
+        };
+        Some(value)
+    }
+}
+
+impl MarkerExpression {
+    /// Evaluating the comparison with `extra` as the value of the `extra` variable
+    ///
+    /// Comparison operators compare as PEP 440 versions if the right side is a valid specifier version and the left
+    /// side a valid version, e.g. `python_full_version >= "3.8"` or `python_version == "3.*"`. Otherwise they
+    /// compare as strings, where `~=` is never true. Comparisons with `extra` are between normalized names.
+    pub fn evaluate(&self, environment: &MarkerEnvironment, extra: &str) -> bool {
+        let involves_extra = [&self.left, &self.right].contains(&&MarkerValue::Variable(MarkerVariable::Extra));
+        let resolve = |value: &MarkerValue| -> String {
+            let value = match value {
+                MarkerValue::Variable(variable) => environment.get(*variable).unwrap_or(extra),
+                MarkerValue::String(string) => string,
+            };
+            if involves_extra {
+                normalize(value)
+            }
+            else {
+                value.to_string()
+            }
+        };
+        let (left, right) = (resolve(&self.left), resolve(&self.right));
+        let operator = match self.operator {
+            MarkerOperator::Compare(operator) => operator,
+            MarkerOperator::In => return right.contains(&left),
+            MarkerOperator::NotIn => return !right.contains(&left),
+        };
+        if !involves_extra {
+            let specifier = parse_version_specifier(format!("{}{}", operator, right));
+            if let (Ok(specifier), Ok(version)) = (specifier, parse_version(left.clone())) {
+                return specifier.contains(&version);
+            }
+        }
+        match operator {
+            Operator::Equal | Operator::ArbitraryEqual => left == right,
+            Operator::NotEqual => left != right,
+            Operator::LessThanEqual => left <= right,
+            Operator::GreaterThanEqual => left >= right,
+            Operator::LessThan => left < right,
+            Operator::GreaterThan => left > right,
+            Operator::Compatible => false,
+        }
+    }
+}
+
+impl MarkerTree {
+    /// Whether the marker holds in `environment` for a distribution installed with `extras`
+    ///
+    /// Like `packaging`, the marker is evaluated once per extra (or once with an empty `extra` if there are none)
+    /// and holds if any of those evaluations is true, so `extra == "a" and extra == "b"` never holds.
+    pub fn evaluate(&self, environment: &MarkerEnvironment, extras: &[&str]) -> bool {
+        match extras {
+            [] => self.evaluate_extra(environment, ""),
+            extras => extras.iter().any(|extra| self.evaluate_extra(environment, extra)),
+        }
+    }
+
+    fn evaluate_extra(&self, environment: &MarkerEnvironment, extra: &str) -> bool {
+        match self {
+            MarkerTree::Expression(expression) => expression.evaluate(environment, extra),
+            MarkerTree::And(children) => children.iter().all(|child| child.evaluate_extra(environment, extra)),
+            MarkerTree::Or(children) => children.iter().any(|child| child.evaluate_extra(environment, extra)),
+        }
+    }
+}
+
+impl MarkerTree {
+    /// The marker that holds everywhere
+    pub const TRUE: MarkerTree = MarkerTree::And(Vec::new());
+    /// The marker that holds nowhere
+    pub const FALSE: MarkerTree = MarkerTree::Or(Vec::new());
+
+    /// The marker holding where both `self` and `other` hold
+    pub fn and(self, other: MarkerTree) -> MarkerTree {
+        let mut children = Vec::new();
+        for tree in [self, other] {
+            match tree {
+                MarkerTree::And(grandchildren) => children.extend(grandchildren),
+                tree => children.push(tree),
+            }
+        }
+        match children.len() {
+            1 => children.pop().unwrap(),
+            _ => MarkerTree::And(children),
+        }
+    }
+
+    /// The marker holding where `self` or `other` holds
+    pub fn or(self, other: MarkerTree) -> MarkerTree {
+        let mut children = Vec::new();
+        for tree in [self, other] {
+            match tree {
+                MarkerTree::Or(grandchildren) => children.extend(grandchildren),
+                tree => children.push(tree),
+            }
+        }
+        match children.len() {
+            1 => children.pop().unwrap(),
+            _ => MarkerTree::Or(children),
+        }
+    }
+
+    /// Rewriting the marker in a canonical disjunctive normal form, an `or` of `and`s of comparisons
+    ///
+    /// Comparisons of `python_version`, `python_full_version` and `implementation_version` with versions are merged
+    /// per variable as version ranges, `==` and `!=` comparisons with other strings as sets of values, so
+    /// `python_version >= "3.8" or python_version < "3.8"` becomes `MarkerTree::TRUE` and `os_name == "nt" and
+    /// os_name == "posix"` becomes `MarkerTree::FALSE`. Conjunctions implied by others are dropped. Markers that hold
+    /// in the same environments simplify to the same string as long as their variables are independent; the relation
+    /// between `python_version` and `python_full_version` is not taken into account.
+    pub fn simplify(&self) -> MarkerTree {
+        let mut children = dnf(self).iter().flat_map(Conjunction::to_trees).collect::<Vec<MarkerTree>>();
+        children.sort_by_cached_key(ToString::to_string);
+        children.dedup();
+        match children.len() {
+            1 => children.pop().unwrap(),
+            _ => MarkerTree::Or(children),
+        }
+    }
+
+    /// Whether the marker holds in every environment, e.g. `os_name == "nt" or os_name != "nt"`
+    pub fn is_true(&self) -> bool {
+        not_dnf(&dnf(self)).is_empty()
+    }
+
+    /// Whether the marker holds in no environment, e.g. `python_version < "3" and python_version > "3.8"`
+    pub fn is_false(&self) -> bool {
+        dnf(self).is_empty()
+    }
+
+    /// Whether `other` holds wherever this marker holds, e.g. `python_version >= "3.9"` implies
+    /// `python_version >= "3.8"`
+    ///
+    /// Like `simplify` this can miss implications that depend on how variables relate to each other.
+    pub fn implies(&self, other: &MarkerTree) -> bool {
+        and_dnf(&dnf(self), &not_dnf(&dnf(other))).is_empty()
+    }
+}
+
+/// The values a string variable can have: any of `values` or, if `excluded`, any but them
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StringSet {
+    values: BTreeSet<String>,
+    excluded: bool,
+}
+
+impl StringSet {
+    fn is_empty(&self) -> bool {
+        !self.excluded && self.values.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.excluded && self.values.is_empty()
+    }
+
+    fn complement(&self) -> Self {
+        StringSet { values: self.values.clone(),
+                    excluded: !self.excluded }
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        let (values, excluded) = match (self.excluded, other.excluded) {
+            (false, false) => (self.values.intersection(&other.values).cloned().collect(), false),
+            (false, true) => (self.values.difference(&other.values).cloned().collect(), false),
+            (true, false) => (other.values.difference(&self.values).cloned().collect(), false),
+            (true, true) => (self.values.union(&other.values).cloned().collect(), true),
+        };
+        StringSet { values, excluded }
+    }
+}
+
+/// The values a variable can have within one conjunction of a marker
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Domain {
+    Versions(VersionRange),
+    Strings(StringSet),
+}
+
+impl Domain {
+    fn is_empty(&self) -> bool {
+        match self {
+            Domain::Versions(range) => range.is_empty(),
+            Domain::Strings(set) => set.is_empty(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        match self {
+            Domain::Versions(range) => *range == VersionRange::full(),
+            Domain::Strings(set) => set.is_full(),
+        }
+    }
+
+    fn complement(&self) -> Self {
+        match self {
+            Domain::Versions(range) => Domain::Versions(range.complement()),
+            Domain::Strings(set) => Domain::Strings(set.complement()),
+        }
+    }
+
+    /// Intersecting two domains of the same variable, which are always of the same kind
+    fn intersection(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Domain::Versions(range), Domain::Versions(other)) => Domain::Versions(range.intersection(other)),
+            (Domain::Strings(set), Domain::Strings(other)) => Domain::Strings(set.intersection(other)),
+            _ => unreachable!(),
+        }
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        self.complement().intersection(&other.complement()).complement()
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        self.intersection(&other.complement()).is_empty()
+    }
+}
+
+/// Writing a range as one specifier set or, if it has a gap no `!=` excludes, one set per interval, choosing the
+/// shortest of the sets `candidates` gives for the same segments and the first without a dev-release among those
+fn specifier_sets(range: &VersionRange, candidates: impl Fn(Vec<Segment>) -> Vec<Vec<Segment>>) -> Vec<SpecifierSet> {
+    let shortest = |segments: Vec<Segment>| {
+        candidates(segments).into_iter()
+                            .filter_map(|segments| VersionRange::from_segments(segments).to_specifier_set())
+                            .min_by_key(|specifiers| (specifiers.specifiers.len(), specifiers.to_string().contains(".dev")))
+    };
+    match shortest(range.segments().to_vec()) {
+        Some(specifiers) => vec![specifiers],
+        None => range.segments()
+                     .iter()
+                     .map(|segment| shortest(vec![segment.clone()]).expect("an interval can always be written as specifiers"))
+                     .collect(),
+    }
+}
+
+/// Moving a bound of a `python_version` range to right before the dev- and pre-releases of the final release `V` next
+/// to it or right after its post-releases, depending on whether `V` is in the range
+///
+/// `python_version` is always a final `major.minor` release, so this keeps the versions it can be while making ranges
+/// that only differ around such releases equal, e.g. `< "3.8"` and `>= "3.8"` complements of each other.
+fn snap_python_version_bound(bound: &Bound<Version>, lower: bool) -> Bound<Version> {
+    let version = match bound {
+        Unbounded => return Unbounded,
+        Included(version) | Excluded(version) => version,
+    };
+    let release = normalized(version.epoch, version.release.clone(), None, None, None);
+    let contains_release = match bound {
+        Included(version) if lower => *version <= release,
+        Excluded(version) if lower => *version < release,
+        Included(version) => *version >= release,
+        _ => *version > release,
+    };
+    // The cut is after `V` if a lower bound excludes it or an upper bound includes it
+    let cut = if contains_release != lower {
+        normalized(release.epoch, release.release.clone(), None, Some(0), Some(0))
+    }
+    else {
+        normalized(release.epoch, release.release.clone(), None, None, Some(0))
+    };
+    if lower {
+        Included(cut)
+    }
+    else {
+        Excluded(cut)
+    }
+}
+
+fn snap_python_version(range: &VersionRange) -> VersionRange {
+    VersionRange::from_segments(range.segments()
+                                     .iter()
+                                     .map(|(lower, upper)| {
+                                         (snap_python_version_bound(lower, true), snap_python_version_bound(upper, false))
+                                     })
+                                     .collect())
+}
+
+/// The final release a snapped bound cuts before (`dev0`) or after (`post0.dev0`)
+fn cut_release(version: &Version, post: Option<u64>) -> Option<Version> {
+    (version.pre.is_none() && version.post == post && version.dev == Some(0)).then(|| {
+                                                                                 normalized(version.epoch,
+                                                                                            version.release.clone(),
+                                                                                            None,
+                                                                                            None,
+                                                                                            None)
+                                                                             })
+}
+
+/// Writing a snapped `python_version` range with the bounds of `>=`, `>`, `<`, `<=` and `!=` where that is shorter
+/// than writing the snapped bounds, which give e.g. `== "3.*"`
+fn python_version_specifier_sets(range: &VersionRange) -> Vec<SpecifierSet> {
+    specifier_sets(range, |mut segments| {
+        // A gap of exactly one release is written as `!=`
+        for index in 1..segments.len() {
+            let upper = match &segments[index - 1].1 {
+                Excluded(upper) => cut_release(upper, None),
+                _ => None,
+            };
+            let lower = match &segments[index].0 {
+                Included(lower) => cut_release(lower, Some(0)),
+                _ => None,
+            };
+            if let (Some(upper), Some(lower)) = (upper, lower) {
+                if upper == lower {
+                    segments[index - 1].1 = Excluded(upper);
+                }
+            }
+        }
+        let mut released = segments.clone();
+        if let Some((lower, _)) = released.first_mut() {
+            if let Included(version) = lower {
+                if let Some(release) = cut_release(version, None) {
+                    *lower = Included(release);
+                }
+                else if let Some(release) = cut_release(version, Some(0)) {
+                    *lower = Excluded(normalized(release.epoch, release.release.clone(), None, Some(u64::MAX), None));
+                }
+            }
+        }
+        vec![segments, released]
+    })
+}
+
+/// A marker comparison as a constraint on one variable, a comparison kept as it is (possibly negated) or a constant
+enum Literal {
+    Domain(MarkerVariable, Domain),
+    Comparison(MarkerExpression, bool),
+    Constant(bool),
+}
+
+impl Literal {
+    fn negate(self) -> Literal {
+        match self {
+            Literal::Domain(variable, domain) => Literal::Domain(variable, domain.complement()),
+            Literal::Comparison(expression, negated) => Literal::Comparison(expression, !negated),
+            Literal::Constant(value) => Literal::Constant(!value),
+        }
+    }
+}
+
+/// The operator that holds exactly where `operator` does not, for comparisons of strings
+fn negated_operator(operator: MarkerOperator) -> MarkerOperator {
+    match operator {
+        MarkerOperator::Compare(Operator::Equal | Operator::ArbitraryEqual) => MarkerOperator::Compare(Operator::NotEqual),
+        MarkerOperator::Compare(Operator::NotEqual) => MarkerOperator::Compare(Operator::Equal),
+        MarkerOperator::Compare(Operator::LessThan) => MarkerOperator::Compare(Operator::GreaterThanEqual),
+        MarkerOperator::Compare(Operator::GreaterThanEqual) => MarkerOperator::Compare(Operator::LessThan),
+        MarkerOperator::Compare(Operator::LessThanEqual) => MarkerOperator::Compare(Operator::GreaterThan),
+        MarkerOperator::Compare(Operator::GreaterThan) => MarkerOperator::Compare(Operator::LessThanEqual),
+        MarkerOperator::Compare(Operator::Compatible) => MarkerOperator::Compare(Operator::Compatible),
+        MarkerOperator::In => MarkerOperator::NotIn,
+        MarkerOperator::NotIn => MarkerOperator::In,
+    }
+}
+
+/// The operator that holds with its operands swapped, e.g. `>=` for `<=`
+fn mirrored_operator(operator: Operator) -> Operator {
+    match operator {
+        Operator::LessThan => Operator::GreaterThan,
+        Operator::GreaterThan => Operator::LessThan,
+        Operator::LessThanEqual => Operator::GreaterThanEqual,
+        Operator::GreaterThanEqual => Operator::LessThanEqual,
+        operator => operator,
+    }
+}
+
+impl MarkerExpression {
+    /// The expression as a literal of the normal form
+    fn literal(&self) -> Literal {
+        let is_version_variable = |variable: &MarkerVariable| {
+            matches!(variable,
+                     MarkerVariable::PythonVersion | MarkerVariable::PythonFullVersion | MarkerVariable::ImplementationVersion)
+        };
+        let (variable, value, operator) = match (&self.left, self.operator, &self.right) {
+            (MarkerValue::String(_), _, MarkerValue::String(_)) => {
+                return Literal::Constant(self.evaluate(&MarkerEnvironment::default(), ""))
+            }
+            (MarkerValue::Variable(MarkerVariable::Extra), MarkerOperator::Compare(operator), MarkerValue::String(value))
+            | (MarkerValue::String(value), MarkerOperator::Compare(operator), MarkerValue::Variable(MarkerVariable::Extra)) => {
+                (MarkerVariable::Extra, normalize(value), operator)
+            }
+            (MarkerValue::Variable(variable), MarkerOperator::Compare(operator), MarkerValue::String(value)) => {
+                match parse_version_specifier(format!("{}{}", operator, value)) {
+                    Ok(specifier) if is_version_variable(variable) => return self.version_literal(*variable, &specifier),
+                    Ok(_) => return Literal::Comparison(self.clone(), false),
+                    Err(_) => (*variable, value.clone(), operator),
+                }
+            }
+            // `"3.8" <= python_version` compares like `python_version >= "3.8"` if the value is a version, and a value
+            // that is not is only compared as a string if it is not a specifier the other way around either, e.g. not
+            // `"3.*" == platform_release`
+            (MarkerValue::String(value), MarkerOperator::Compare(operator), MarkerValue::Variable(variable)) => {
+                let mirrored = mirrored_operator(operator);
+                match (parse_version(value.clone()), parse_version_specifier(format!("{}{}", mirrored, value))) {
+                    (Ok(_), Ok(specifier)) if is_version_variable(variable) && operator != Operator::Compatible => {
+                        return self.version_literal(*variable, &specifier)
+                    }
+                    (Err(_), Err(_)) => (*variable, value.clone(), mirrored),
+                    _ => return Literal::Comparison(self.clone(), false),
+                }
+            }
+            (_, MarkerOperator::In | MarkerOperator::NotIn, _) => return self.string_comparison(),
+            _ => return Literal::Comparison(self.clone(), false),
+        };
+        // What is left are string comparisons of a variable with a value that is not a version
+        match operator {
+            Operator::Equal | Operator::ArbitraryEqual | Operator::NotEqual if !is_version_variable(&variable) => {
+                let set = StringSet { values: BTreeSet::from([value]),
+                                      excluded: operator == Operator::NotEqual };
+                Literal::Domain(variable, Domain::Strings(set))
+            }
+            Operator::Compatible => Literal::Constant(false),
+            _ => self.string_comparison(),
+        }
+    }
+
+    /// The comparison of a version variable with `specifier` as the range of versions it allows
+    fn version_literal(&self, variable: MarkerVariable, specifier: &VersionSpecifier) -> Literal {
+        match VersionRange::from_specifier(specifier) {
+            Ok(range) if variable == MarkerVariable::PythonVersion => {
+                Literal::Domain(variable, Domain::Versions(snap_python_version(&range)))
+            }
+            Ok(range) => Literal::Domain(variable, Domain::Versions(range)),
+            Err(_) => Literal::Comparison(self.clone(), false),
+        }
+    }
+
+    /// A comparison of strings, kept with the operator of `==`/`!=`, `<`/`>=`, `<=`/`>` and `in`/`not in` that comes
+    /// first, so that a comparison and its negation end up as the same comparison
+    fn string_comparison(&self) -> Literal {
+        let negated = matches!(self.operator,
+                               MarkerOperator::Compare(Operator::NotEqual | Operator::GreaterThanEqual | Operator::GreaterThan)
+                               | MarkerOperator::NotIn);
+        let operator = match (self.operator, negated) {
+            (MarkerOperator::Compare(Operator::ArbitraryEqual), _) => MarkerOperator::Compare(Operator::Equal),
+            (operator, true) => negated_operator(operator),
+            (operator, false) => operator,
+        };
+        Literal::Comparison(MarkerExpression { operator,
+                                               ..self.clone() },
+                            negated)
+    }
+}
+
+/// One `and` of a marker in disjunctive normal form, with at most one domain per variable
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Conjunction {
+    domains: BTreeMap<MarkerVariable, Domain>,
+    /// The comparisons that are kept as they are by their string, with whether they are negated
+    comparisons: BTreeMap<String, (MarkerExpression, bool)>,
+}
+
+impl Conjunction {
+    /// Adding a literal, returning false if that makes the conjunction unsatisfiable
+    fn and_literal(&mut self, literal: Literal) -> bool {
+        match literal {
+            Literal::Constant(value) => value,
+            Literal::Domain(variable, domain) => {
+                let domain = match self.domains.remove(&variable) {
+                    Some(own) => own.intersection(&domain),
+                    None => domain,
+                };
+                if !domain.is_full() {
+                    self.domains.insert(variable, domain.clone());
+                }
+                !domain.is_empty()
+            }
+            Literal::Comparison(expression, negated) => match self.comparisons.entry(expression.to_string()) {
+                Entry::Occupied(entry) => entry.get().1 == negated,
+                Entry::Vacant(entry) => {
+                    entry.insert((expression, negated));
+                    true
+                }
+            },
+        }
+    }
+
+    fn literals(&self) -> Vec<Literal> {
+        let domains = self.domains
+                          .iter()
+                          .map(|(variable, domain)| Literal::Domain(*variable, domain.clone()));
+        let comparisons = self.comparisons
+                              .values()
+                              .map(|(expression, negated)| Literal::Comparison(expression.clone(), *negated));
+        domains.chain(comparisons).collect()
+    }
+
+    fn and(&self, other: &Conjunction) -> Option<Conjunction> {
+        let mut conjunction = self.clone();
+        other.literals()
+             .into_iter()
+             .all(|literal| conjunction.and_literal(literal))
+             .then_some(conjunction)
+    }
+
+    fn implies(&self, other: &Conjunction) -> bool {
+        other.domains
+             .iter()
+             .all(|(variable, domain)| self.domains.get(variable).is_some_and(|own| own.is_subset(domain)))
+        && other.comparisons
+                .iter()
+                .all(|(key, (_, negated))| self.comparisons.get(key).is_some_and(|(_, own)| own == negated))
+    }
+
+    /// Uniting two conjunctions that constrain the same variables and comparisons and differ in only one of them
+    fn merge(&self, other: &Conjunction) -> Option<Conjunction> {
+        if !self.domains.keys().eq(other.domains.keys()) || !self.comparisons.keys().eq(other.comparisons.keys()) {
+            return None;
+        }
+        let domains = self.domains
+                          .iter()
+                          .filter(|(variable, domain)| other.domains[*variable] != **domain)
+                          .collect::<Vec<_>>();
+        let comparisons = self.comparisons
+                              .iter()
+                              .filter(|(key, comparison)| other.comparisons[*key] != **comparison)
+                              .collect::<Vec<_>>();
+        let mut merged = self.clone();
+        match (domains.as_slice(), comparisons.as_slice()) {
+            ([(variable, domain)], []) => {
+                let union = domain.union(&other.domains[*variable]);
+                merged.domains.remove(*variable);
+                if !union.is_full() {
+                    merged.domains.insert(**variable, union);
+                }
+            }
+            // A comparison or its negation always holds
+            ([], [(key, _)]) => {
+                merged.comparisons.remove(*key);
+            }
+            _ => return None,
+        }
+        Some(merged)
+    }
+
+    /// The conjunction as `and`s of comparisons, several if a variable's values need an `or`, e.g. a version range
+    /// with a gap or a set of strings
+    fn to_trees(&self) -> Vec<MarkerTree> {
+        let comparison = |variable: MarkerVariable, operator: Operator, value: String| {
+            MarkerExpression { left: MarkerValue::Variable(variable),
+                               operator: MarkerOperator::Compare(operator),
+                               right: MarkerValue::String(value) }
+        };
+        // For every variable and comparison the alternatives of the comparisons that are `and`-ed
+        let mut alternatives: Vec<Vec<Vec<MarkerExpression>>> = Vec::new();
+        for (variable, domain) in &self.domains {
+            match domain {
+                Domain::Versions(range) => {
+                    let sets = match variable {
+                        MarkerVariable::PythonVersion => python_version_specifier_sets(range),
+                        _ => specifier_sets(range, |segments| vec![segments]),
+                    };
+                    alternatives.push(sets.iter()
+                                          .map(|specifiers| {
+                                              specifiers.specifiers
+                                                        .iter()
+                                                        .map(|specifier| {
+                                                            let wildcard = if specifier.wildcard { ".*" } else { "" };
+                                                            comparison(*variable,
+                                                                       specifier.operator,
+                                                                       format!("{}{}", specifier.version, wildcard))
+                                                        })
+                                                        .collect()
+                                          })
+                                          .collect());
+                }
+                Domain::Strings(set) if set.excluded => {
+                    alternatives.push(vec![set.values
+                                              .iter()
+                                              .map(|value| comparison(*variable, Operator::NotEqual, value.clone()))
+                                              .collect()]);
+                }
+                Domain::Strings(set) => {
+                    alternatives.push(set.values
+                                         .iter()
+                                         .map(|value| vec![comparison(*variable, Operator::Equal, value.clone())])
+                                         .collect());
+                }
+            }
+        }
+        for (expression, negated) in self.comparisons.values() {
+            // Only `implies` negates comparisons that have no exact negation, and it never writes them
+            let operator = if *negated {
+                negated_operator(expression.operator)
+            }
+            else {
+                expression.operator
+            };
+            alternatives.push(vec![vec![MarkerExpression { operator,
+                                                           ..expression.clone() }]]);
+        }
+
+        let mut conjunctions: Vec<Vec<MarkerExpression>> = vec![Vec::new()];
+        for alternatives in alternatives {
+            conjunctions = conjunctions.iter()
+                                       .flat_map(|conjunction| {
+                                           alternatives.iter().map(move |alternative| {
+                                                                  let mut conjunction = conjunction.clone();
+                                                                  conjunction.extend(alternative.iter().cloned());
+                                                                  conjunction
+                                                              })
+                                       })
+                                       .collect();
+        }
+        conjunctions.into_iter()
+                    .map(|mut expressions| match expressions.len() {
+                        1 => MarkerTree::Expression(expressions.pop().unwrap()),
+                        _ => MarkerTree::And(expressions.into_iter().map(MarkerTree::Expression).collect()),
+                    })
+                    .collect()
+    }
+}
+
+/// Dropping unsatisfiable conjunctions, conjunctions implied by others and merging the ones that differ in one
+/// constraint, until nothing changes
+fn reduce(mut conjunctions: Vec<Conjunction>) -> Vec<Conjunction> {
+    'reduce: loop {
+        for i in 0..conjunctions.len() {
+            for j in 0..conjunctions.len() {
+                if i == j {
+                    continue;
+                }
+                // Of two equal conjunctions the first one is kept
+                if conjunctions[i].implies(&conjunctions[j]) && (i > j || !conjunctions[j].implies(&conjunctions[i])) {
+                    conjunctions.remove(i);
+                    continue 'reduce;
+                }
+                if let Some(merged) = conjunctions[i].merge(&conjunctions[j]) {
+                    conjunctions[i] = merged;
+                    conjunctions.remove(j);
+                    continue 'reduce;
+                }
+            }
+        }
+        return conjunctions;
+    }
+}
+
+/// The conjunctions at least one of which holds where `tree` holds
+fn dnf(tree: &MarkerTree) -> Vec<Conjunction> {
+    match tree {
+        MarkerTree::Expression(expression) => {
+            let mut conjunction = Conjunction::default();
+            if conjunction.and_literal(expression.literal()) {
+                vec![conjunction]
+            }
+            else {
+                Vec::new()
+            }
+        }
+        MarkerTree::And(children) => children.iter().fold(vec![Conjunction::default()], |conjunctions, child| {
+                                                        and_dnf(&conjunctions, &dnf(child))
+                                                    }),
+        MarkerTree::Or(children) => reduce(children.iter().flat_map(dnf).collect()),
+    }
+}
+
+fn and_dnf(left: &[Conjunction], right: &[Conjunction]) -> Vec<Conjunction> {
+    reduce(left.iter()
+               .flat_map(|left| right.iter().filter_map(move |right| left.and(right)))
+               .collect())
+}
+
+fn not_dnf(conjunctions: &[Conjunction]) -> Vec<Conjunction> {
+    conjunctions.iter()
+                .fold(vec![Conjunction::default()], |negated, conjunction| {
+                    let alternatives = conjunction.literals()
+                                                  .into_iter()
+                                                  .filter_map(|literal| {
+                                                      let mut alternative = Conjunction::default();
+                                                      alternative.and_literal(literal.negate()).then_some(alternative)
+                                                  })
+                                                  .collect::<Vec<Conjunction>>();
+                    and_dnf(&negated, &alternatives)
+                })
+}
+
+/// A position in a requirement or marker string, shared by the PEP 508 parsers
+pub(crate) struct Cursor<'a> {
+    pub(crate) input: &'a str,
+    pub(crate) position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Cursor { input, position: 0 }
+    }
+
+    pub(crate) fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub(crate) fn at_end(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    /// Skipping whitespace, returning whether there was any
+    pub(crate) fn whitespace(&mut self) -> bool {
+        let start = self.position;
+        self.position = self.input.len() - self.rest().trim_start().len();
+        self.position > start
+    }
+
+    /// Consuming `token` if the input continues with it
+    pub(crate) fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            return true;
+        }
+        false
+    }
+
+    /// Consuming the longest run of characters matching `predicate`, returning its span
+    pub(crate) fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> Range<usize> {
+        let start = self.position;
+        self.position += self.rest().find(|c: char| !predicate(c)).unwrap_or(self.rest().len());
+        start..self.position
+    }
+
+    /// Consuming `word` if the input continues with it as a whole word
+    fn keyword(&mut self, word: &str) -> bool {
+        let rest = self.rest();
+        if rest.starts_with(word) && !rest[word.len()..].starts_with(is_identifier_char) {
+            self.position += word.len();
+            return true;
+        }
+        false
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+fn invalid_marker(cursor: &Cursor, span: Range<usize>, reason: &str) -> Error {
+    Error::InvalidMarker { input: cursor.input.to_string(),
+                           span,
+                           reason: reason.to_string() }
+}
+
+/// `marker_or = marker_and ('or' marker_and)*`
+pub(crate) fn parse_marker_or(cursor: &mut Cursor) -> Result<MarkerTree, Error> {
+    let mut children = Vec::new();
+    loop {
+        match parse_marker_and(cursor)? {
+            MarkerTree::Or(grandchildren) => children.extend(grandchildren),
+            child => children.push(child),
+        }
+        cursor.whitespace();
+        if !cursor.keyword("or") {
+            break;
+        }
+    }
+    Ok(match children.len() {
+        1 => children.pop().unwrap(),
+        _ => MarkerTree::Or(children),
+    })
+}
+
+/// `marker_and = marker_expr ('and' marker_expr)*`
+fn parse_marker_and(cursor: &mut Cursor) -> Result<MarkerTree, Error> {
+    let mut children = Vec::new();
+    loop {
+        match parse_marker_expression(cursor)? {
+            MarkerTree::And(grandchildren) => children.extend(grandchildren),
+            child => children.push(child),
+        }
+        cursor.whitespace();
+        if !cursor.keyword("and") {
+            break;
+        }
+    }
+    Ok(match children.len() {
+        1 => children.pop().unwrap(),
+        _ => MarkerTree::And(children),
+    })
+}
+
+/// `marker_expr = marker_var marker_op marker_var | '(' marker ')'`
+fn parse_marker_expression(cursor: &mut Cursor) -> Result<MarkerTree, Error> {
+    cursor.whitespace();
+    let start = cursor.position;
+    if cursor.eat("(") {
+        let marker = parse_marker_or(cursor)?;
+        cursor.whitespace();
+        if !cursor.eat(")") {
+            return Err(invalid_marker(cursor, start..cursor.position, "expected ) to close this ("));
+        }
+        return Ok(marker);
+    }
+    let left = parse_marker_value(cursor)?;
+    let operator = parse_marker_operator(cursor)?;
+    let right = parse_marker_value(cursor)?;
+    Ok(MarkerTree::Expression(MarkerExpression { left, operator, right }))
+}
+
+/// `marker_var = env_var | python_str`
+fn parse_marker_value(cursor: &mut Cursor) -> Result<MarkerValue, Error> {
+    cursor.whitespace();
+    let start = cursor.position;
+    match cursor.peek() {
+        Some(quote @ ('"' | '\'')) => {
+            cursor.position += 1;
+            let string = cursor.take_while(|c| c != quote);
+            if !cursor.eat(&quote.to_string()) {
+                return Err(invalid_marker(cursor, start..cursor.position, "unterminated string"));
+            }
+            Ok(MarkerValue::String(cursor.input[string].to_string()))
+        }
+        _ => {
+            let name = cursor.take_while(is_identifier_char);
+            match MarkerVariable::ALL.iter()
+                                     .find(|(variable, _)| *variable == &cursor.input[name.clone()])
+            {
+                Some((_, variable)) => Ok(MarkerValue::Variable(*variable)),
+                None => {
+                    let span = if name.is_empty() {
+                        start..start + cursor.peek().map_or(0, char::len_utf8)
+                    }
+                    else {
+                        name
+                    };
+                    Err(invalid_marker(cursor,
+                                       span,
+                                       "expected a marker variable like python_version or a quoted string"))
+                }
+            }
+        }
+    }
+}
+
+/// `marker_op = version_cmp | 'in' | 'not' 'in'`
+fn parse_marker_operator(cursor: &mut Cursor) -> Result<MarkerOperator, Error> {
+    const COMPARISONS: [(&str, Operator); 8] = [("===", Operator::ArbitraryEqual),
+                                                ("==", Operator::Equal),
+                                                ("!=", Operator::NotEqual),
+                                                ("<=", Operator::LessThanEqual),
+                                                (">=", Operator::GreaterThanEqual),
+                                                ("~=", Operator::Compatible),
+                                                ("<", Operator::LessThan),
+                                                (">", Operator::GreaterThan)];
+    cursor.whitespace();
+    let start = cursor.position;
+    if let Some((_, operator)) = COMPARISONS.iter().find(|(token, _)| cursor.eat(token)) {
+        return Ok(MarkerOperator::Compare(*operator));
+    }
+    if cursor.keyword("in") {
+        return Ok(MarkerOperator::In);
+    }
+    if cursor.keyword("not") && cursor.whitespace() && cursor.keyword("in") {
+        return Ok(MarkerOperator::NotIn);
+    }
+    cursor.position = start;
+    let word = cursor.take_while(|c| !c.is_whitespace());
+    Err(invalid_marker(cursor, word, "expected a comparison operator like == or in"))
+}
+
+/// Parsing an environment marker, e.g. `python_version >= "3.8" and sys_platform == "linux"`
+///
+/// Args:
+/// - `marker_string` (String): A PEP 508 marker, the part of a requirement after `;`
+///
+/// Returns `Result<MarkerTree, Error>`: `Error::InvalidMarker` with the span of the first thing that does not fit the
+/// grammar
+pub fn parse_marker(marker_string: String) -> Result<MarkerTree, Error> {
+    let mut cursor = Cursor::new(&marker_string);
+    let marker = parse_marker_or(&mut cursor)?;
+    cursor.whitespace();
+    if !cursor.at_end() {
+        return Err(invalid_marker(&cursor,
+                                  cursor.position..marker_string.len(),
+                                  "expected and, or or the end of the marker"));
+    }
+    Ok(marker)
+}
+
+impl FromStr for MarkerTree {
+    type Err = Error;
+
+    fn from_str(marker_string: &str) -> Result<Self, Self::Err> {
+        parse_marker(marker_string.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MarkerTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for MarkerTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse_marker(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
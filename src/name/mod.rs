@@ -0,0 +1,227 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Error;
+
+/// Normalizing a package or extra name the way PEP 503 and PEP 685 compare them: lowercase, with every run of `-`, `_`
+/// and `.` replaced by a single `-`, e.g. `foo-bar` for `Foo__Bar` and `FOO.bar`
+pub fn normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        }
+        else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+/// Checking `name` against the PEP 508 name grammar, `^([A-Z0-9]|[A-Z0-9][A-Z0-9._-]*[A-Z0-9])$` ignoring case,
+/// returning the span of the violation and what is wrong with it
+fn validate(name: &str, what: &str) -> Result<(), (Range<usize>, String)> {
+    if name.is_empty() {
+        return Err((0..0, format!("{} must not be empty", what)));
+    }
+    if let Some((index, c)) = name.char_indices()
+                                  .find(|(_, c)| !c.is_ascii_alphanumeric() && !matches!(c, '-' | '_' | '.'))
+    {
+        return Err((index..index + c.len_utf8(), format!("{} may only contain letters, digits, -, _ and .", what)));
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphanumeric()) || !name.ends_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err((0..name.len(), format!("{} must start and end with a letter or digit", what)));
+    }
+    Ok(())
+}
+
+fn invalid_name(input: String, (span, reason): (Range<usize>, String)) -> Error {
+    Error::InvalidName { input, span, reason }
+}
+
+/// The name of a distribution, e.g. `Foo_Bar`
+///
+/// Names keep the spelling they were written in but compare, hash and sort by their PEP 503 normalized form, so
+/// `Foo_Bar`, `foo-bar` and `FOO.bar` are the same package.
+#[derive(Debug, Clone)]
+pub struct PackageName {
+    name: String,
+    normalized: String,
+}
+
+impl PackageName {
+    /// The name as it was written
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// The normalized name, e.g. `foo-bar` for `Foo_Bar`, as used in the URLs of the simple repository API
+    pub fn normalized(&self) -> &str {
+        &self.normalized
+    }
+}
+
+impl PartialEq for PackageName {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized == other.normalized
+    }
+}
+
+impl Eq for PackageName {}
+
+impl Hash for PackageName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normalized.hash(state);
+    }
+}
+
+impl PartialOrd for PackageName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PackageName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.normalized.cmp(&other.normalized)
+    }
+}
+
+impl fmt::Display for PackageName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Parsing a package name, e.g. the value of the `Name` header
+///
+/// Args:
+/// - `name_string` (String): A name matching the PEP 508 name grammar
+///
+/// Returns `Result<PackageName, Error>`: `Error::InvalidName` with the span of the offending part otherwise
+pub fn parse_package_name(name_string: String) -> Result<PackageName, Error> {
+    if let Err(violation) = validate(&name_string, "a package name") {
+        return Err(invalid_name(name_string, violation));
+    }
+    Ok(PackageName { normalized: normalize(&name_string),
+                     name: name_string })
+}
+
+impl FromStr for PackageName {
+    type Err = Error;
+
+    fn from_str(name_string: &str) -> Result<Self, Self::Err> {
+        parse_package_name(name_string.to_string())
+    }
+}
+
+/// The name of an optional feature of a distribution, e.g. `Dev_Tools`
+///
+/// Like `PackageName`, extras keep their spelling but compare, hash and sort by their PEP 685 normalized form.
+#[derive(Debug, Clone)]
+pub struct ExtraName {
+    name: String,
+    normalized: String,
+}
+
+impl ExtraName {
+    /// The extra as it was written
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// The normalized extra, e.g. `dev-tools` for `Dev_Tools`, as compared with `extra` in markers
+    pub fn normalized(&self) -> &str {
+        &self.normalized
+    }
+}
+
+impl PartialEq for ExtraName {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized == other.normalized
+    }
+}
+
+impl Eq for ExtraName {}
+
+impl Hash for ExtraName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normalized.hash(state);
+    }
+}
+
+impl PartialOrd for ExtraName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExtraName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.normalized.cmp(&other.normalized)
+    }
+}
+
+impl fmt::Display for ExtraName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Parsing an extra name, e.g. the value of a `Provides-Extra` header
+///
+/// Args:
+/// - `extra_string` (String): A name matching the PEP 508 name grammar
+///
+/// Returns `Result<ExtraName, Error>`: `Error::InvalidName` with the span of the offending part otherwise
+pub fn parse_extra_name(extra_string: String) -> Result<ExtraName, Error> {
+    if let Err(violation) = validate(&extra_string, "an extra name") {
+        return Err(invalid_name(extra_string, violation));
+    }
+    Ok(ExtraName { normalized: normalize(&extra_string),
+                   name: extra_string })
+}
+
+impl FromStr for ExtraName {
+    type Err = Error;
+
+    fn from_str(extra_string: &str) -> Result<Self, Self::Err> {
+        parse_extra_name(extra_string.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PackageName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PackageName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse_package_name(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ExtraName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ExtraName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse_extra_name(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
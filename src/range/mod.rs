@@ -0,0 +1,490 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+use crate::bump::normalized;
+use crate::specifier::{Operator, SpecifierSet, VersionSpecifier};
+use crate::{AnyVersion, Error, Release, Version};
+
+/// One interval of a `VersionRange`, from its lower to its upper bound
+pub type Segment = (Bound<Version>, Bound<Version>);
+
+/// A set of versions, stored as sorted, disjoint and non-adjacent intervals
+///
+/// Ranges are built from version specifiers and can be intersected, united and complemented, e.g. to find out that
+/// `>=2,<1.5` is unsatisfiable. They only deal with public versions, local labels are ignored, and know nothing about
+/// the pre-release policy of a `SpecifierSet`.
+///
+/// Bounds are kept half-open (`[lower, upper)`) wherever the version right after a bound exists, so that equal sets of
+/// versions have equal segments. That is not the case after all post-releases of a version, e.g. `>1.0` has no smallest
+/// version and is kept as `(1.0.post18446744073709551615, ∞)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VersionRange {
+    segments: Vec<Segment>,
+}
+
+/// The smallest version there is, `0.dev0`
+fn is_minimum(version: &Version) -> bool {
+    version.epoch.unwrap_or(0) == 0
+    && version.release.iter().all(|n| *n == 0)
+    && version.pre.is_none()
+    && version.post.is_none()
+    && version.dev == Some(0)
+}
+
+/// `version` with other post- and dev-release parts
+fn with_post_dev(version: &Version, post: Option<u64>, dev: Option<u64>) -> Version {
+    normalized(version.epoch, version.release.clone(), version.pre.clone(), post, dev)
+}
+
+/// The version right after `version`, e.g. `1.0.dev1` after `1.0.dev0` and `1.0.post0.dev0` after `1.0`, ignoring local
+/// labels and `None` if a number would overflow
+fn successor(version: &Version) -> Option<Version> {
+    let (post, dev) = match (version.post, version.dev) {
+        (post, Some(dev)) => (post, Some(dev.checked_add(1)?)),
+        (Some(post), None) => (Some(post.checked_add(1)?), Some(0)),
+        (None, None) => (Some(0), Some(0)),
+    };
+    Some(with_post_dev(version, post, dev))
+}
+
+/// The smallest version starting with `prefix`, or with `prefix` with its last segment incremented if `next`
+fn prefix_bound(epoch: Option<u64>, prefix: &[u64], next: bool) -> Version {
+    let mut release = Release::from_slice(prefix);
+    if next {
+        if let Some(last) = release.last_mut() {
+            *last = last.saturating_add(1);
+        }
+    }
+    normalized(epoch, release, None, None, Some(0))
+}
+
+fn cmp_lower(this: &Bound<Version>, other: &Bound<Version>) -> Ordering {
+    match (this, other) {
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Less,
+        (_, Unbounded) => Ordering::Greater,
+        (Included(this), Included(other)) | (Excluded(this), Excluded(other)) => this.cmp(other),
+        (Included(this), Excluded(other)) => this.cmp(other).then(Ordering::Less),
+        (Excluded(this), Included(other)) => this.cmp(other).then(Ordering::Greater),
+    }
+}
+
+fn cmp_upper(this: &Bound<Version>, other: &Bound<Version>) -> Ordering {
+    match (this, other) {
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Greater,
+        (_, Unbounded) => Ordering::Less,
+        (Included(this), Included(other)) | (Excluded(this), Excluded(other)) => this.cmp(other),
+        (Included(this), Excluded(other)) => this.cmp(other).then(Ordering::Greater),
+        (Excluded(this), Included(other)) => this.cmp(other).then(Ordering::Less),
+    }
+}
+
+/// Turning the end of a segment into the start of the gap after it and vice versa
+fn flip(bound: &Bound<Version>) -> Bound<Version> {
+    match bound {
+        Included(version) => Excluded(version.clone()),
+        Excluded(version) => Included(version.clone()),
+        Unbounded => Unbounded,
+    }
+}
+
+fn normalize_lower(bound: Bound<Version>) -> Bound<Version> {
+    match bound {
+        Included(version) if is_minimum(&version) => Unbounded,
+        Excluded(version) => match successor(&version) {
+            Some(next) => Included(next),
+            None => Excluded(version),
+        },
+        bound => bound,
+    }
+}
+
+fn normalize_upper(bound: Bound<Version>) -> Bound<Version> {
+    match bound {
+        Included(version) => match successor(&version) {
+            Some(next) => Excluded(next),
+            None => Included(version),
+        },
+        bound => bound,
+    }
+}
+
+fn is_empty_segment((lower, upper): &Segment) -> bool {
+    match (lower, upper) {
+        (_, Excluded(upper)) if is_minimum(upper) => true,
+        (Unbounded, _) | (_, Unbounded) => false,
+        (Included(lower), Included(upper)) => lower > upper,
+        (Included(lower) | Excluded(lower), Included(upper) | Excluded(upper)) => lower >= upper,
+    }
+}
+
+/// Whether a segment ending at `upper` overlaps or touches one starting at `lower`
+fn touches(upper: &Bound<Version>, lower: &Bound<Version>) -> bool {
+    match (upper, lower) {
+        (Unbounded, _) | (_, Unbounded) => true,
+        (Excluded(upper), Excluded(lower)) => lower < upper,
+        (Included(upper) | Excluded(upper), Included(lower) | Excluded(lower)) => lower <= upper,
+    }
+}
+
+fn above_lower(version: &Version, lower: &Bound<Version>) -> bool {
+    match lower {
+        Unbounded => true,
+        Included(lower) => version >= lower,
+        Excluded(lower) => version > lower,
+    }
+}
+
+fn below_upper(version: &Version, upper: &Bound<Version>) -> bool {
+    match upper {
+        Unbounded => true,
+        Included(upper) => version <= upper,
+        Excluded(upper) => version < upper,
+    }
+}
+
+fn specifier(operator: Operator, version: Version, wildcard: bool) -> VersionSpecifier {
+    VersionSpecifier { operator,
+                       version: AnyVersion::Pep440(version),
+                       wildcard }
+}
+
+/// The version a segment holds if it holds only one
+fn point(lower: &Bound<Version>, upper: &Bound<Version>) -> Option<Version> {
+    match (lower, upper) {
+        (Included(lower), Excluded(upper)) if successor(lower).as_ref() == Some(upper) => Some(lower.clone()),
+        (Included(lower), Included(upper)) if lower == upper => Some(lower.clone()),
+        _ => None,
+    }
+}
+
+/// `release` zero padded or truncated to `length` segments, `None` if that would drop a non-zero segment
+fn padded(release: &[u64], length: usize) -> Option<Release> {
+    if release.iter().skip(length).any(|n| *n != 0) {
+        return None;
+    }
+    Some((0..length).map(|index| release.get(index).copied().unwrap_or(0)).collect())
+}
+
+/// The release prefix `P` of a segment that is exactly `==P.*`
+fn wildcard_prefix(lower: &Bound<Version>, upper: &Bound<Version>) -> Option<Version> {
+    let (lower, upper) = match (lower, upper) {
+        (Included(lower), Excluded(upper)) => (lower, upper),
+        _ => return None,
+    };
+    if lower.pre.is_some() || lower.post.is_some() || lower.dev != Some(0) {
+        return None;
+    }
+    for length in 1..=lower.release.len().max(upper.release.len()) {
+        if let Some(prefix) = padded(&lower.release, length) {
+            if prefix_bound(lower.epoch, &prefix, true) == *upper {
+                return Some(normalized(lower.epoch, prefix, None, None, None));
+            }
+        }
+    }
+    None
+}
+
+/// The version `V` of a segment that is exactly `~=V`
+fn compatible_version(lower: &Bound<Version>, upper: &Bound<Version>) -> Option<Version> {
+    let (lower, upper) = match (lower, upper) {
+        (Included(lower), Excluded(upper)) => (lower, upper),
+        _ => return None,
+    };
+    for length in 2..=lower.release.len().max(upper.release.len() + 1) {
+        if let Some(release) = padded(&lower.release, length) {
+            if prefix_bound(lower.epoch, &release[..length - 1], true) == *upper {
+                return Some(normalized(lower.epoch, release, lower.pre.clone(), lower.post, lower.dev));
+            }
+        }
+    }
+    None
+}
+
+/// The version `V.devN` of the two lowest segments of a range starting with `>V.devN`
+///
+/// `>V.devN` excludes the post-releases of `V`, so it is `[V.devN+1, V.post0.dev0) ∪ (V.post∞, …`.
+fn greater_than_dev(first: &Segment, second: &Segment) -> Option<Version> {
+    let (lower, upper, post) = match (first, &second.0) {
+        ((Included(lower), Excluded(upper)), Excluded(post)) => (lower, upper, post),
+        _ => return None,
+    };
+    if lower.post.is_some()
+       || *upper != with_post_dev(lower, Some(0), Some(0))
+       || *post != with_post_dev(lower, Some(u64::MAX), None)
+    {
+        return None;
+    }
+    let dev = lower.dev?.checked_sub(1)?;
+    Some(with_post_dev(lower, None, Some(dev)))
+}
+
+/// The fewest specifiers that together contain exactly the versions between `lower` and `upper`
+fn segment_specifiers(lower: &Bound<Version>, upper: &Bound<Version>) -> Option<Vec<VersionSpecifier>> {
+    if let Some(version) = point(lower, upper) {
+        return Some(vec![specifier(Operator::Equal, version, false)]);
+    }
+    if let Some(prefix) = wildcard_prefix(lower, upper) {
+        return Some(vec![specifier(Operator::Equal, prefix, true)]);
+    }
+    if let Some(version) = compatible_version(lower, upper) {
+        return Some(vec![specifier(Operator::Compatible, version, false)]);
+    }
+
+    let mut specifiers = Vec::new();
+    match lower {
+        Unbounded => {}
+        Included(version) => specifiers.push(specifier(Operator::GreaterThanEqual, version.clone(), false)),
+        // `>V` excludes the post-releases of `V` unless it is one
+        Excluded(version) if version.post == Some(u64::MAX) && version.dev.is_none() => {
+            specifiers.push(specifier(Operator::GreaterThan, with_post_dev(version, None, None), false))
+        }
+        Excluded(version) if version.is_postrelease() => {
+            specifiers.push(specifier(Operator::GreaterThan, version.clone(), false))
+        }
+        Excluded(_) => return None,
+    }
+    match upper {
+        Unbounded => {}
+        // Right after all post-releases of `V`, which no specifier can express
+        Included(version) if version.post == Some(u64::MAX) && version.dev.is_none() => return None,
+        Included(version) => specifiers.push(specifier(Operator::LessThanEqual, version.clone(), false)),
+        // The version right after `V` is `V.post0.dev0`
+        Excluded(version) if version.post == Some(0) && version.dev == Some(0) => {
+            specifiers.push(specifier(Operator::LessThanEqual, with_post_dev(version, None, None), false))
+        }
+        // `<V` excludes the pre-releases of `V` unless it is one
+        Excluded(version) if version.dev == Some(0) && version.pre.is_none() => {
+            specifiers.push(specifier(Operator::LessThan, with_post_dev(version, version.post, None), false))
+        }
+        Excluded(version) if version.is_prerelease() => specifiers.push(specifier(Operator::LessThan, version.clone(), false)),
+        Excluded(version) => {
+            specifiers.push(specifier(Operator::LessThanEqual, version.clone(), false));
+            specifiers.push(specifier(Operator::NotEqual, version.clone(), false));
+        }
+    }
+    Some(specifiers)
+}
+
+/// The specifier excluding the versions between `lower` and `upper`, if there is one
+fn gap_specifier(lower: &Bound<Version>, upper: &Bound<Version>) -> Option<VersionSpecifier> {
+    if let Some(version) = point(lower, upper) {
+        return Some(specifier(Operator::NotEqual, version, false));
+    }
+    wildcard_prefix(lower, upper).map(|prefix| specifier(Operator::NotEqual, prefix, true))
+}
+
+impl VersionRange {
+    /// The range containing every version
+    pub fn full() -> Self {
+        VersionRange { segments: vec![(Unbounded, Unbounded)] }
+    }
+
+    /// The range containing no version
+    pub fn empty() -> Self {
+        VersionRange { segments: Vec::new() }
+    }
+
+    /// Building a range from any intervals, normalizing their bounds and merging the ones that overlap or touch
+    pub(crate) fn from_segments(segments: Vec<Segment>) -> Self {
+        let mut segments = segments.into_iter()
+                                   .map(|(lower, upper)| (normalize_lower(lower), normalize_upper(upper)))
+                                   .filter(|segment| !is_empty_segment(segment))
+                                   .collect::<Vec<Segment>>();
+        segments.sort_by(|(this, _), (other, _)| cmp_lower(this, other));
+
+        let mut merged: Vec<Segment> = Vec::new();
+        for (lower, upper) in segments {
+            match merged.last_mut() {
+                Some((_, last_upper)) if touches(last_upper, &lower) => {
+                    if cmp_upper(&upper, last_upper) == Ordering::Greater {
+                        *last_upper = upper;
+                    }
+                }
+                _ => merged.push((lower, upper)),
+            }
+        }
+        VersionRange { segments: merged }
+    }
+
+    /// Building the range of versions a specifier contains
+    ///
+    /// Returns `Error::InvalidSpecifier` for `===` and specifiers with a local label, which do not describe a range of
+    /// public versions.
+    pub fn from_specifier(specifier: &VersionSpecifier) -> Result<Self, Error> {
+        let version = match &specifier.version {
+            AnyVersion::Pep440(version) if specifier.operator != Operator::ArbitraryEqual && version.local.is_none() => version,
+            _ => {
+                let input = specifier.to_string();
+                return Err(Error::InvalidSpecifier { span: 0..input.len(),
+                                                     input,
+                                                     reason:
+                                                         "=== and local versions do not describe a version range".to_string() });
+            }
+        };
+        let segments = match specifier.operator {
+            Operator::Equal | Operator::NotEqual if specifier.wildcard => {
+                vec![(Included(prefix_bound(version.epoch, &version.release, false)),
+                      Excluded(prefix_bound(version.epoch, &version.release, true)))]
+            }
+            Operator::Equal | Operator::NotEqual => vec![(Included(version.clone()), Included(version.clone()))],
+            Operator::Compatible => {
+                let prefix = &version.release[..version.release.len() - 1];
+                vec![(Included(version.clone()), Excluded(prefix_bound(version.epoch, prefix, true)))]
+            }
+            Operator::LessThanEqual => vec![(Unbounded, Included(version.clone()))],
+            Operator::GreaterThanEqual => vec![(Included(version.clone()), Unbounded)],
+            Operator::LessThan if version.is_prerelease() => vec![(Unbounded, Excluded(version.clone()))],
+            Operator::LessThan => vec![(Unbounded, Excluded(with_post_dev(version, version.post, Some(0))))],
+            Operator::GreaterThan if version.is_postrelease() => vec![(Excluded(version.clone()), Unbounded)],
+            Operator::GreaterThan => {
+                let mut segments = vec![(Excluded(with_post_dev(version, Some(u64::MAX), None)), Unbounded)];
+                if version.is_devrelease() {
+                    segments.push((Excluded(version.clone()), Excluded(with_post_dev(version, Some(0), Some(0)))));
+                }
+                segments
+            }
+            Operator::ArbitraryEqual => unreachable!(),
+        };
+        let range = VersionRange::from_segments(segments);
+        match specifier.operator {
+            Operator::NotEqual => Ok(range.complement()),
+            _ => Ok(range),
+        }
+    }
+
+    /// Building the range of versions every specifier of a set contains, ignoring its pre-release policy
+    pub fn from_specifier_set(specifiers: &SpecifierSet) -> Result<Self, Error> {
+        specifiers.specifiers
+                  .iter()
+                  .try_fold(VersionRange::full(), |range, specifier| {
+                      Ok(range.intersection(&VersionRange::from_specifier(specifier)?))
+                  })
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Whether no version at all is in the range, e.g. for `>=2,<1.5`
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Whether the public part of `version` is in the range
+    pub fn contains(&self, version: &Version) -> bool {
+        let version = match version.local {
+            Some(_) => Cow::Owned(version.public()),
+            None => Cow::Borrowed(version),
+        };
+        self.segments
+            .iter()
+            .any(|(lower, upper)| above_lower(&version, lower) && below_upper(&version, upper))
+    }
+
+    /// The versions in both ranges
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut segments = Vec::new();
+        for (lower, upper) in &self.segments {
+            for (other_lower, other_upper) in &other.segments {
+                let lower = match cmp_lower(lower, other_lower) {
+                    Ordering::Greater => lower,
+                    _ => other_lower,
+                };
+                let upper = match cmp_upper(upper, other_upper) {
+                    Ordering::Less => upper,
+                    _ => other_upper,
+                };
+                segments.push((lower.clone(), upper.clone()));
+            }
+        }
+        VersionRange::from_segments(segments)
+    }
+
+    /// The versions in either range
+    pub fn union(&self, other: &Self) -> Self {
+        VersionRange::from_segments(self.segments.iter().chain(&other.segments).cloned().collect())
+    }
+
+    /// The versions not in the range
+    pub fn complement(&self) -> Self {
+        let mut segments = Vec::new();
+        let mut lower = Some(Unbounded);
+        for (segment_lower, segment_upper) in &self.segments {
+            if let (Some(lower), false) = (lower.take(), matches!(segment_lower, Unbounded)) {
+                segments.push((lower, flip(segment_lower)));
+            }
+            lower = match segment_upper {
+                Unbounded => None,
+                upper => Some(flip(upper)),
+            };
+        }
+        if let Some(lower) = lower {
+            segments.push((lower, Unbounded));
+        }
+        VersionRange::from_segments(segments)
+    }
+
+    /// Rendering the range as the fewest specifiers that contain exactly its versions, e.g. `>=1.0,<2,!=1.5.*`
+    ///
+    /// The full range gives an empty set and the empty range `<0.dev0`. A range with a gap that no single `!=`
+    /// specifier excludes, e.g. `<1 || >2`, or ending right after all post-releases of a version, e.g. the complement of
+    /// `>1.0`, cannot be written as a specifier set and gives `None`.
+    pub fn to_specifier_set(&self) -> Option<SpecifierSet> {
+        let (first, last) = match (self.segments.first(), self.segments.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => {
+                return Some(SpecifierSet { specifiers: vec![specifier(Operator::LessThan,
+                                                                      prefix_bound(None, &[0], false),
+                                                                      false)],
+                                           allow_prereleases: None })
+            }
+        };
+        let (mut specifiers, segments) = match self.segments
+                                                   .get(..2)
+                                                   .and_then(|lowest| greater_than_dev(&lowest[0], &lowest[1]))
+        {
+            Some(version) => {
+                let mut specifiers = vec![specifier(Operator::GreaterThan, version, false)];
+                specifiers.extend(segment_specifiers(&Unbounded, &last.1)?);
+                (specifiers, &self.segments[1..])
+            }
+            None => (segment_specifiers(&first.0, &last.1)?, &self.segments[..]),
+        };
+        for window in segments.windows(2) {
+            let lower = normalize_lower(flip(&window[0].1));
+            let upper = normalize_upper(flip(&window[1].0));
+            specifiers.push(gap_specifier(&lower, &upper)?);
+        }
+        Some(SpecifierSet { specifiers,
+                            allow_prereleases: None })
+    }
+}
+
+/// Writing the range in interval notation, e.g. `[1.0, 1.5.dev0) ∪ [2.0, ∞)`, see `to_specifier_set` for specifiers
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return write!(f, "∅");
+        }
+        for (index, (lower, upper)) in self.segments.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ∪ ")?;
+            }
+            match lower {
+                Unbounded => write!(f, "(-∞, ")?,
+                Included(version) => write!(f, "[{}, ", version)?,
+                Excluded(version) => write!(f, "({}, ", version)?,
+            }
+            match upper {
+                Unbounded => write!(f, "∞)")?,
+                Included(version) => write!(f, "{}]", version)?,
+                Excluded(version) => write!(f, "{})", version)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::marker::{parse_marker_or, Cursor, MarkerEnvironment, MarkerTree};
+use crate::name::{parse_extra_name, parse_package_name, ExtraName, PackageName};
+use crate::specifier::{parse_specifier_set, SpecifierSet};
+use crate::Error;
+
+/// A dependency specification, e.g. `requests[security,socks] >=2.8.1, <3 ; python_version < "3.8"`
+///
+/// A requirement either restricts the version with `specifiers` (which may be empty) or points to a `url`, see
+/// https://peps.python.org/pep-0508/.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    pub name: PackageName,
+    pub extras: Vec<ExtraName>,
+    pub specifiers: SpecifierSet,
+    pub url: Option<String>,
+    pub marker: Option<MarkerTree>,
+}
+
+impl Requirement {
+    /// Whether this requirement applies in `environment` when installing with `extras`, i.e. it has no marker or its
+    /// marker holds
+    pub fn applies(&self, environment: &MarkerEnvironment, extras: &[&str]) -> bool {
+        self.marker.as_ref().is_none_or(|marker| marker.evaluate(environment, extras))
+    }
+}
+
+/// Writing the requirement the way `packaging` does, e.g. `name[extra]>=1.0,<2; python_version < "3.8"`
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.extras.is_empty() {
+            let extras = self.extras.iter().map(ExtraName::as_str).collect::<Vec<&str>>();
+            write!(f, "[{}]", extras.join(","))?;
+        }
+        write!(f, "{}", self.specifiers)?;
+        // A marker that always holds, e.g. a simplified one, is left out
+        let marker = self.marker.as_ref().filter(|marker| **marker != MarkerTree::TRUE);
+        if let Some(url) = &self.url {
+            write!(f, " @ {}", url)?;
+            if marker.is_some() {
+                write!(f, " ")?;
+            }
+        }
+        if let Some(marker) = marker {
+            write!(f, "; {}", marker)?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid_requirement(cursor: &Cursor, span: Range<usize>, reason: &str) -> Error {
+    Error::InvalidRequirement { input: cursor.input.to_string(),
+                                span,
+                                reason: reason.to_string() }
+}
+
+/// `identifier = letterOrDigit (('-' | '_' | '.')* letterOrDigit)*`, parsed with `parse` as a name or an extra
+fn parse_identifier<T>(cursor: &mut Cursor, what: &str, parse: fn(String) -> Result<T, Error>) -> Result<T, Error> {
+    let span = cursor.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if span.is_empty() {
+        let span = span.start..span.start + cursor.peek().map_or(0, char::len_utf8);
+        return Err(invalid_requirement(cursor, span, &format!("expected {}", what)));
+    }
+    parse(cursor.input[span.clone()].to_string()).map_err(|error| match error {
+                                                     Error::InvalidName { span: name, reason, .. } => {
+                                                         invalid_requirement(cursor,
+                                                                             span.start + name.start..span.start + name.end,
+                                                                             &reason)
+                                                     }
+                                                     error => error,
+                                                 })
+}
+
+/// `extras = '[' (identifier (',' identifier)*)? ']'`
+fn parse_extras(cursor: &mut Cursor) -> Result<Vec<ExtraName>, Error> {
+    let start = cursor.position;
+    let mut extras = Vec::new();
+    cursor.eat("[");
+    cursor.whitespace();
+    if cursor.eat("]") {
+        return Ok(extras);
+    }
+    loop {
+        cursor.whitespace();
+        extras.push(parse_identifier(cursor, "an extra name", parse_extra_name)?);
+        cursor.whitespace();
+        if cursor.eat("]") {
+            return Ok(extras);
+        }
+        if !cursor.eat(",") {
+            let span = if cursor.at_end() {
+                start..cursor.position
+            }
+            else {
+                cursor.position..cursor.position + cursor.peek().map_or(0, char::len_utf8)
+            };
+            return Err(invalid_requirement(cursor, span, "expected , or ] after an extra name"));
+        }
+    }
+}
+
+/// `versionspec = '(' version_many ')' | version_many`, parsed as a `SpecifierSet`
+fn parse_specifiers(cursor: &mut Cursor) -> Result<SpecifierSet, Error> {
+    let start = cursor.position;
+    let parenthesized = cursor.eat("(");
+    let end = if parenthesized {
+        match cursor.rest().find(')') {
+            Some(end) => cursor.position + end,
+            None => {
+                return Err(invalid_requirement(cursor,
+                                               start..cursor.input.len(),
+                                               "expected ) to close this ("))
+            }
+        }
+    }
+    else {
+        cursor.rest()
+              .find(';')
+              .map_or(cursor.input.len(), |end| cursor.position + end)
+    };
+    let specifiers = parse_specifier_set(cursor.input[cursor.position..end].to_string()).map_err(|error| {
+                                                                                            error.within(cursor.input,
+                                                                                                         cursor.position)
+                                                                                        })?;
+    cursor.position = end;
+    if parenthesized {
+        cursor.eat(")");
+    }
+    Ok(specifiers)
+}
+
+/// Parsing a PEP 508 dependency specification, e.g. `name[extra] >=1.0 ; python_version < "3.8"` or
+/// `name @ https://example.com/name.zip`
+///
+/// Args:
+/// - `requirement_string` (String): A requirement, e.g. the value of a `Requires-Dist` header
+///
+/// Returns `Result<Requirement, Error>`: `Error::InvalidRequirement`, `Error::InvalidMarker` or a specifier error, all
+/// with spans into `requirement_string`
+pub fn parse_requirement(requirement_string: String) -> Result<Requirement, Error> {
+    let mut cursor = Cursor::new(&requirement_string);
+    cursor.whitespace();
+    let name = parse_identifier(&mut cursor, "a package name", parse_package_name)?;
+
+    cursor.whitespace();
+    let extras = match cursor.peek() {
+        Some('[') => parse_extras(&mut cursor)?,
+        _ => Vec::new(),
+    };
+
+    cursor.whitespace();
+    let mut url = None;
+    let mut specifiers = SpecifierSet::default();
+    match cursor.peek() {
+        Some('@') => {
+            cursor.eat("@");
+            cursor.whitespace();
+            let span = cursor.take_while(|c| !c.is_whitespace());
+            if span.is_empty() {
+                return Err(invalid_requirement(&cursor,
+                                               span.start..requirement_string.len(),
+                                               "expected a URL after @"));
+            }
+            url = Some(requirement_string[span].to_string());
+            // A `;` right after the URL is part of it, so markers need whitespace in front
+            cursor.whitespace();
+            if !cursor.at_end() && cursor.peek() != Some(';') {
+                let span = cursor.position..requirement_string.trim_end().len();
+                return Err(invalid_requirement(&cursor, span, "expected ; and markers after the URL"));
+            }
+        }
+        Some('(' | '<' | '>' | '=' | '!' | '~') => specifiers = parse_specifiers(&mut cursor)?,
+        _ => {}
+    }
+
+    cursor.whitespace();
+    let marker = if cursor.eat(";") {
+        Some(parse_marker_or(&mut cursor)?)
+    }
+    else {
+        None
+    };
+
+    cursor.whitespace();
+    if !cursor.at_end() {
+        let span = cursor.position..requirement_string.len();
+        if marker.is_some() {
+            return Err(Error::InvalidMarker { input: requirement_string.clone(),
+                                              span,
+                                              reason: "expected and, or or the end of the marker".to_string() });
+        }
+        return Err(invalid_requirement(&cursor, span, "expected a version specifier, @ and a URL or ; and markers"));
+    }
+
+    Ok(Requirement { name,
+                     extras,
+                     specifiers,
+                     url,
+                     marker })
+}
+
+impl FromStr for Requirement {
+    type Err = Error;
+
+    fn from_str(requirement_string: &str) -> Result<Self, Self::Err> {
+        parse_requirement(requirement_string.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Requirement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Requirement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse_requirement(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,415 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::str::FromStr;
+
+use either::Either;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{parse_any_version, parse_version, AnyVersion, Error, Version};
+
+/// A version comparison operator, see https://peps.python.org/pep-0440/#version-specifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    /// `~=`, e.g. `~=1.4.2` is `>=1.4.2, ==1.4.*`
+    Compatible,
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<=`
+    LessThanEqual,
+    /// `>=`
+    GreaterThanEqual,
+    /// `<`, excluding pre-releases of the given version
+    LessThan,
+    /// `>`, excluding post-releases of the given version
+    GreaterThan,
+    /// `===`, comparing version strings rather than versions
+    ArbitraryEqual,
+}
+
+impl Operator {
+    /// All operators, longest first so that the first one a specifier starts with is the right one
+    const ALL: [(&'static str, Operator); 8] = [("===", Operator::ArbitraryEqual),
+                                                ("~=", Operator::Compatible),
+                                                ("==", Operator::Equal),
+                                                ("!=", Operator::NotEqual),
+                                                ("<=", Operator::LessThanEqual),
+                                                (">=", Operator::GreaterThanEqual),
+                                                ("<", Operator::LessThan),
+                                                (">", Operator::GreaterThan)];
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (operator, _) = Operator::ALL.iter().find(|(_, operator)| operator == self).unwrap();
+        write!(f, "{}", operator)
+    }
+}
+
+/// A single version specifier, e.g. `>=1.2`, `~=2.0.1` or `==1.4.*`
+///
+/// The version is a PEP 440 version for every operator but `===`, which compares against any string and so may hold a
+/// `LegacyVersion`. A `wildcard` specifier (only `==` and `!=`) matches every version whose release starts with the
+/// release of `version`, which then has neither pre-, post- nor dev-release nor local label.
+#[derive(Debug, Clone)]
+pub struct VersionSpecifier {
+    pub operator: Operator,
+    pub version: AnyVersion,
+    pub wildcard: bool,
+}
+
+impl VersionSpecifier {
+    /// Whether `version` satisfies this specifier
+    ///
+    /// Pre-releases are matched like any other version, only `<` and `>` treat them (and post-releases) specially:
+    /// `<1.0` does not contain `1.0rc1` and `>1.0` does not contain `1.0.post1`, unless the specifier's version is
+    /// itself such a release. Like PEP 440 says, only pre-releases of exactly the given version are excluded, so
+    /// `<1.0.post1` contains `1.0rc1` but not `1.0.post1.dev0`, and `>1.0rc1` contains `1.0.post1` but not
+    /// `1.0rc1.post1`.
+    pub fn contains(&self, version: &Version) -> bool {
+        let spec = match &self.version {
+            AnyVersion::Pep440(spec) if self.operator != Operator::ArbitraryEqual => spec,
+            spec => return version.version_string.trim().eq_ignore_ascii_case(version_text(spec)),
+        };
+        match self.operator {
+            Operator::Compatible => {
+                let prefix = &spec.release[..spec.release.len() - 1];
+                version.public() >= *spec && matches_prefix(version, spec.epoch.unwrap_or(0), prefix)
+            }
+            Operator::Equal if self.wildcard => matches_prefix(version, spec.epoch.unwrap_or(0), &spec.release),
+            Operator::NotEqual if self.wildcard => !matches_prefix(version, spec.epoch.unwrap_or(0), &spec.release),
+            Operator::Equal => matches_exactly(version, spec),
+            Operator::NotEqual => !matches_exactly(version, spec),
+            Operator::LessThanEqual => version.public() <= *spec,
+            Operator::GreaterThanEqual => version.public() >= *spec,
+            Operator::LessThan => version < spec && (spec.is_prerelease() || !is_prerelease_of(version, spec)),
+            Operator::GreaterThan => {
+                version > spec
+                && (spec.is_postrelease() || !is_postrelease_of(version, spec))
+                && !(version.local.is_some() && version.public() == *spec)
+            }
+            Operator::ArbitraryEqual => unreachable!(),
+        }
+    }
+
+    /// Whether this specifier explicitly asks for a pre-release, e.g. `>=1.0rc1` or `==2.0.dev0`, which makes a
+    /// `SpecifierSet` containing it accept pre-releases
+    pub fn requests_prereleases(&self) -> bool {
+        let inclusive = matches!(self.operator,
+                                 Operator::Compatible
+                                 | Operator::Equal
+                                 | Operator::LessThanEqual
+                                 | Operator::GreaterThanEqual
+                                 | Operator::ArbitraryEqual);
+        inclusive && self.version.as_pep440().is_some_and(Version::is_prerelease)
+    }
+
+    /// Building the key specifiers are compared by: trailing zeros are significant for `~=` and wildcards, since
+    /// `~=1.4` allows `1.5` but `~=1.4.0` does not, and `===` compares case-insensitive strings
+    fn key(&self) -> (Operator, bool, Option<usize>, Either<String, &AnyVersion>) {
+        let release_length = match &self.version {
+            AnyVersion::Pep440(version) if self.wildcard || self.operator == Operator::Compatible => Some(version.release.len()),
+            _ => None,
+        };
+        let version = match self.operator {
+            Operator::ArbitraryEqual => Either::Left(version_text(&self.version).to_ascii_lowercase()),
+            _ => Either::Right(&self.version),
+        };
+        (self.operator, self.wildcard, release_length, version)
+    }
+}
+
+impl PartialEq for VersionSpecifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for VersionSpecifier {}
+
+impl Hash for VersionSpecifier {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// The string a specifier was written with, which `===` compares against
+fn version_text(version: &AnyVersion) -> &str {
+    match version {
+        AnyVersion::Pep440(version) => version.version_string.trim(),
+        AnyVersion::Legacy(version) => version.version_string.trim(),
+    }
+}
+
+/// Whether `version` and `spec` have the same epoch and release, ignoring trailing zeros
+fn same_release(version: &Version, spec: &Version) -> bool {
+    let trimmed = |release: &[u64]| release.len() - release.iter().rev().take_while(|n| **n == 0).count();
+    version.epoch.unwrap_or(0) == spec.epoch.unwrap_or(0)
+    && version.release[..trimmed(&version.release)] == spec.release[..trimmed(&spec.release)]
+}
+
+/// Whether `version` is a pre-release of the final or post-release `spec`, i.e. sorts between `spec` with a `.dev0`
+/// suffix and `spec`, e.g. `1.0rc1` for `1.0` and `1.0.post1.dev0` for `1.0.post1`
+fn is_prerelease_of(version: &Version, spec: &Version) -> bool {
+    same_release(version, spec)
+    && match spec.post {
+        None => version.is_prerelease(),
+        Some(post) => version.pre.is_none() && version.post == Some(post) && version.is_devrelease(),
+    }
+}
+
+/// Whether `version` is a post-release of `spec`, e.g. `1.0.post1` for `1.0` and `1.0rc1.post1` for `1.0rc1.dev2`
+fn is_postrelease_of(version: &Version, spec: &Version) -> bool {
+    same_release(version, spec) && version.pre == spec.pre && version.is_postrelease()
+}
+
+/// `==` without a wildcard: a specifier without local label matches every local version of its public version
+fn matches_exactly(version: &Version, spec: &Version) -> bool {
+    match spec.local {
+        Some(_) => version == spec,
+        None => version.public() == *spec,
+    }
+}
+
+/// Whether the release of `version`, zero padded to the length of `prefix`, starts with `prefix`
+///
+/// https://peps.python.org/pep-0440/#version-matching
+fn matches_prefix(version: &Version, epoch: u64, prefix: &[u64]) -> bool {
+    version.epoch.unwrap_or(0) == epoch
+    && prefix.iter()
+             .enumerate()
+             .all(|(index, n)| version.release.get(index).copied().unwrap_or(0) == *n)
+}
+
+/// Writing the operator followed by the normalized version, or the original string for `===`
+impl fmt::Display for VersionSpecifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operator {
+            Operator::ArbitraryEqual => write!(f, "{}{}", self.operator, version_text(&self.version)),
+            _ if self.wildcard => write!(f, "{}{}.*", self.operator, self.version),
+            _ => write!(f, "{}{}", self.operator, self.version),
+        }
+    }
+}
+
+fn invalid_specifier(input: &str, span: Range<usize>, reason: &str) -> Error {
+    Error::InvalidSpecifier { input: input.to_string(),
+                              span,
+                              reason: reason.to_string() }
+}
+
+/// Parsing a version specifier, e.g. `>= 1.2`, `!=2.0.*` or `===foobar`
+///
+/// Args:
+/// - `specifier_string` (String): An operator followed by a version, optionally surrounded by whitespace
+///
+/// Returns `Result<VersionSpecifier, Error>`: `Error::InvalidSpecifier` if the operator is missing or does not allow the
+/// version or wildcard, `Error::InvalidVersion`/`Error::NumericOverflow` with spans into `specifier_string` if the version is invalid
+pub fn parse_version_specifier(specifier_string: String) -> Result<VersionSpecifier, Error> {
+    let operator_start = specifier_string.len() - specifier_string.trim_start().len();
+    let rest = &specifier_string[operator_start..];
+    let (operator_string, operator) = match Operator::ALL.iter().find(|(operator, _)| rest.starts_with(operator)) {
+        Some(&(operator_string, operator)) => (operator_string, operator),
+        None => {
+            return Err(invalid_specifier(&specifier_string,
+                                         operator_start..operator_start + rest.trim_end().len(),
+                                         "expected one of ~=, ==, !=, <=, >=, <, >, ==="));
+        }
+    };
+    let version_start = operator_start + operator_string.len();
+    let version_string = specifier_string[version_start..].trim_end();
+    let version_start = version_start + (version_string.len() - version_string.trim_start().len());
+    let version_span = version_start..version_start + version_string.trim().len();
+
+    if operator == Operator::ArbitraryEqual {
+        let version_string = &specifier_string[version_span.clone()];
+        if version_string.is_empty() || version_string.contains(char::is_whitespace) {
+            return Err(invalid_specifier(&specifier_string,
+                                         version_span,
+                                         "=== needs a version string without whitespace"));
+        }
+        return Ok(VersionSpecifier { operator,
+                                     version: parse_any_version(version_string.to_string()),
+                                     wildcard: false });
+    }
+
+    let wildcard = specifier_string[version_span.clone()].ends_with(".*");
+    if wildcard && !matches!(operator, Operator::Equal | Operator::NotEqual) {
+        return Err(invalid_specifier(&specifier_string,
+                                     version_span,
+                                     &format!("wildcards are only allowed with == and !=, not {}", operator)));
+    }
+    let prefix_end = if wildcard { version_span.end - 2 } else { version_span.end };
+    let version_string = specifier_string[version_span.start..prefix_end].to_string();
+    let version = parse_version(version_string).map_err(|error| error.within(&specifier_string, version_span.start))?;
+
+    if wildcard && (version.pre.is_some() || version.post.is_some() || version.dev.is_some() || version.local.is_some()) {
+        return Err(invalid_specifier(&specifier_string,
+                                     version_span,
+                                     "wildcards can only follow the release segments, e.g. ==1.4.*"));
+    }
+
+    if version.local.is_some() && !matches!(operator, Operator::Equal | Operator::NotEqual) {
+        return Err(invalid_specifier(&specifier_string,
+                                     version_span,
+                                     &format!("local versions are only allowed with == and !=, not {}", operator)));
+    }
+    if operator == Operator::Compatible && version.release.len() < 2 {
+        return Err(invalid_specifier(&specifier_string,
+                                     version_span,
+                                     "~= needs a version with at least two release segments, e.g. ~=1.0"));
+    }
+    Ok(VersionSpecifier { operator,
+                          version: AnyVersion::Pep440(version),
+                          wildcard })
+}
+
+impl FromStr for VersionSpecifier {
+    type Err = Error;
+
+    fn from_str(specifier_string: &str) -> Result<Self, Self::Err> {
+        parse_version_specifier(specifier_string.to_string())
+    }
+}
+
+/// Comma-separated version specifiers a version has to satisfy all of, e.g. `>=1.2, !=1.3.*, <2`
+///
+/// Following PEP 440, pre-releases are excluded unless a specifier asks for one (e.g. `>=2.0b1`) or, when filtering,
+/// nothing but pre-releases matches. `allow_prereleases` overrides that policy either way.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecifierSet {
+    pub specifiers: Vec<VersionSpecifier>,
+    pub allow_prereleases: Option<bool>,
+}
+
+impl SpecifierSet {
+    /// Whether pre-releases are accepted: `allow_prereleases` if set, `Some(true)` if a specifier asks for a
+    /// pre-release and `None` to accept them only if nothing else matches
+    pub fn prereleases(&self) -> Option<bool> {
+        match self.allow_prereleases {
+            Some(allow) => Some(allow),
+            None if self.specifiers.iter().any(VersionSpecifier::requests_prereleases) => Some(true),
+            None => None,
+        }
+    }
+
+    /// Whether `version` satisfies every specifier, rejecting pre-releases unless they are accepted
+    ///
+    /// A single version cannot tell whether it is the only match, so use `filter` or `best_match` to fall back to
+    /// pre-releases.
+    pub fn contains(&self, version: &Version) -> bool {
+        if version.is_prerelease() && self.prereleases() != Some(true) {
+            return false;
+        }
+        self.specifiers.iter().all(|specifier| specifier.contains(version))
+    }
+
+    /// Keeping the versions that satisfy every specifier, in their original order
+    ///
+    /// If pre-releases are neither allowed nor forbidden and only pre-releases match, those are kept.
+    pub fn filter<V: Borrow<Version>, I: IntoIterator<Item = V>>(&self, versions: I) -> Vec<V> {
+        let prereleases = self.prereleases();
+        let (mut finals, mut pres) = (Vec::new(), Vec::new());
+        for version in versions {
+            if !self.specifiers.iter().all(|specifier| specifier.contains(version.borrow())) {
+                continue;
+            }
+            if version.borrow().is_prerelease() && prereleases != Some(true) {
+                pres.push(version);
+            }
+            else {
+                finals.push(version);
+            }
+        }
+        if finals.is_empty() && prereleases.is_none() {
+            pres
+        }
+        else {
+            finals
+        }
+    }
+
+    /// Picking the newest of `versions` that `filter` keeps, e.g. the version to update to
+    pub fn best_match<V: Borrow<Version>, I: IntoIterator<Item = V>>(&self, versions: I) -> Option<V> {
+        self.filter(versions)
+            .into_iter()
+            .max_by(|this, other| this.borrow().cmp(other.borrow()))
+    }
+}
+
+/// Writing the specifiers separated by commas, e.g. `>=1.2,<2`
+impl fmt::Display for SpecifierSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, specifier) in self.specifiers.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", specifier)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parsing comma-separated version specifiers, e.g. `>=1.2, <2`
+///
+/// Args:
+/// - `specifiers_string` (String): Version specifiers separated by commas, empty ones are skipped
+///
+/// Returns `Result<SpecifierSet, Error>`: A SpecifierSet with the default pre-release policy, or the first specifier's
+/// error with spans into `specifiers_string`
+pub fn parse_specifier_set(specifiers_string: String) -> Result<SpecifierSet, Error> {
+    let mut specifiers = Vec::new();
+    let mut offset = 0;
+    for specifier in specifiers_string.split(',') {
+        if !specifier.trim().is_empty() {
+            let specifier =
+                parse_version_specifier(specifier.to_string()).map_err(|error| error.within(&specifiers_string, offset))?;
+            specifiers.push(specifier);
+        }
+        offset += specifier.len() + 1;
+    }
+    Ok(SpecifierSet { specifiers,
+                      allow_prereleases: None })
+}
+
+impl FromStr for SpecifierSet {
+    type Err = Error;
+
+    fn from_str(specifiers_string: &str) -> Result<Self, Self::Err> {
+        parse_specifier_set(specifiers_string.to_string())
+    }
+}
+
+/// Serializing as the canonical specifier string
+#[cfg(feature = "serde")]
+impl Serialize for VersionSpecifier {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for VersionSpecifier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse_version_specifier(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// Serializing as the comma-separated canonical specifiers, without the pre-release override
+#[cfg(feature = "serde")]
+impl Serialize for SpecifierSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SpecifierSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse_specifier_set(String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt;
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::header::{parse_headers, Header, Headers};
+use crate::name::normalize;
+use crate::{parse_metadata, Error, Version};
+
+/// A metadata version as `(major, minor)`, e.g. `(2, 1)` for `Metadata-Version: 2.1`
+type MetadataVersion = (u64, u64);
+
+/// The metadata versions defined by the core metadata specification
+const KNOWN_VERSIONS: [MetadataVersion; 7] = [(1, 0), (1, 1), (1, 2), (2, 1), (2, 2), (2, 3), (2, 4)];
+
+/// A field of the core metadata specification
+struct Field {
+    name: &'static str,
+    /// The metadata version that introduced the field
+    since: MetadataVersion,
+    /// The metadata version from which on the field should no longer be written
+    deprecated: Option<MetadataVersion>,
+    /// Whether the field may be used more than once
+    multiple: bool,
+}
+
+const fn field(name: &'static str, since: MetadataVersion, multiple: bool) -> Field {
+    Field { name,
+            since,
+            deprecated: None,
+            multiple }
+}
+
+const fn deprecated(name: &'static str, since: MetadataVersion, deprecated: MetadataVersion, multiple: bool) -> Field {
+    Field { name,
+            since,
+            deprecated: Some(deprecated),
+            multiple }
+}
+
+/// Every field of the core metadata specification, in the order the specification lists them
+const FIELDS: [Field; 30] = [field("Metadata-Version", (1, 0), false),
+                             field("Name", (1, 0), false),
+                             field("Version", (1, 0), false),
+                             field("Dynamic", (2, 2), true),
+                             field("Platform", (1, 0), true),
+                             field("Supported-Platform", (1, 1), true),
+                             field("Summary", (1, 0), false),
+                             field("Description", (1, 0), false),
+                             field("Description-Content-Type", (2, 1), false),
+                             field("Keywords", (1, 0), false),
+                             field("Home-page", (1, 0), false),
+                             field("Download-URL", (1, 1), false),
+                             field("Author", (1, 0), false),
+                             field("Author-email", (1, 0), false),
+                             field("Maintainer", (1, 2), false),
+                             field("Maintainer-email", (1, 2), false),
+                             deprecated("License", (1, 0), (2, 4), false),
+                             field("License-Expression", (2, 4), false),
+                             field("License-File", (2, 4), true),
+                             field("Classifier", (1, 1), true),
+                             field("Requires-Dist", (1, 2), true),
+                             field("Requires-Python", (1, 2), false),
+                             field("Requires-External", (1, 2), true),
+                             field("Project-URL", (1, 2), true),
+                             field("Provides-Extra", (2, 1), true),
+                             field("Provides-Dist", (1, 2), true),
+                             field("Obsoletes-Dist", (1, 2), true),
+                             deprecated("Requires", (1, 1), (1, 2), true),
+                             deprecated("Provides", (1, 1), (1, 2), true),
+                             deprecated("Obsoletes", (1, 1), (1, 2), true)];
+
+/// Fields that `Dynamic` must not list, because every distribution of a version has to agree on them
+const STATIC_FIELDS: [&str; 3] = ["Metadata-Version", "Name", "Version"];
+
+/// The metadata version that introduced `field`, e.g. `(1, 2)` for `Requires-Dist`, ignoring case
+pub(crate) fn introduced_in(field: &str) -> Option<MetadataVersion> {
+    FIELDS.iter()
+          .find(|known| known.name.eq_ignore_ascii_case(field))
+          .map(|known| known.since)
+}
+
+/// How serious a `Violation` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Severity {
+    /// The metadata is readable but not what its `Metadata-Version` promises, e.g. a field used before its version
+    Warning,
+    /// The metadata breaks a rule of the specification, e.g. a single-use field used twice
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A rule of the core metadata specification that a metadata document breaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Violation {
+    pub severity: Severity,
+    /// The field the violation is about, as it was written
+    pub field: String,
+    /// The byte range of the offending field (or message body) in the metadata document
+    pub span: Range<usize>,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} {}", self.severity, self.field, self.reason)
+    }
+}
+
+/// Every violation found in a metadata document, ordered by where they occur
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Report {
+    pub metadata_version: Version,
+    pub violations: Vec<Violation>,
+}
+
+impl Report {
+    /// Whether the document breaks no rule, i.e. has no violation worse than a warning
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(|violation| violation.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(|violation| violation.severity == Severity::Warning)
+    }
+}
+
+fn display_version((major, minor): MetadataVersion) -> String {
+    format!("{}.{}", major, minor)
+}
+
+fn violation(severity: Severity, header: &Header, reason: String) -> Violation {
+    Violation { severity,
+                field: header.name.clone(),
+                span: header.span.clone(),
+                reason }
+}
+
+/// Checking `Metadata-Version` itself, which `parse_metadata` only limits to known major versions
+fn check_metadata_version(headers: &Headers, version: MetadataVersion, violations: &mut Vec<Violation>) {
+    if KNOWN_VERSIONS.contains(&version) {
+        return;
+    }
+    let latest = KNOWN_VERSIONS[KNOWN_VERSIONS.len() - 1];
+    let reason = if version > latest {
+        format!("{} is newer than {}, fields it introduced are not checked",
+                display_version(version),
+                display_version(latest))
+    }
+    else {
+        format!("{} is not defined by the core metadata specification",
+                display_version(version))
+    };
+    if let Some(header) = headers.get("Metadata-Version") {
+        violations.push(violation(Severity::Warning, header, reason));
+    }
+}
+
+/// Checking every field against the version that introduced it, the version that deprecated it and whether it may
+/// be repeated
+fn check_fields(headers: &Headers, version: MetadataVersion, violations: &mut Vec<Violation>) {
+    for (index, header) in headers.iter().enumerate() {
+        let field = match FIELDS.iter().find(|field| field.name.eq_ignore_ascii_case(&header.name)) {
+            Some(field) => field,
+            None => {
+                violations.push(violation(Severity::Warning, header, "is not a core metadata field".to_string()));
+                continue;
+            }
+        };
+        if version < field.since {
+            violations.push(violation(Severity::Warning,
+                                      header,
+                                      format!("is only defined since Metadata-Version {}", display_version(field.since))));
+        }
+        if let Some(deprecated) = field.deprecated.filter(|deprecated| version >= *deprecated) {
+            violations.push(violation(Severity::Warning,
+                                      header,
+                                      format!("is deprecated since Metadata-Version {}", display_version(deprecated))));
+        }
+        let repeated = headers.iter()
+                              .take(index)
+                              .any(|previous| previous.name.eq_ignore_ascii_case(&header.name));
+        if !field.multiple && repeated {
+            violations.push(violation(Severity::Error, header, "may only be used once".to_string()));
+        }
+    }
+}
+
+/// Checking the rules that relate fields to each other or to the version
+fn check_rules(headers: &Headers, version: MetadataVersion, violations: &mut Vec<Violation>) {
+    if let Some(body) = headers.body().filter(|body| !body.trim().is_empty()) {
+        let span = headers.source().len() - body.len()..headers.source().len();
+        if version < (2, 1) {
+            violations.push(Violation { severity: Severity::Warning,
+                                        field: "Description".to_string(),
+                                        span: span.clone(),
+                                        reason: "is only allowed in the message body since Metadata-Version 2.1".to_string() });
+        }
+        if let Some(header) = headers.get("Description") {
+            violations.push(violation(Severity::Error,
+                                      header,
+                                      "is given both as a field and as the message body".to_string()));
+        }
+    }
+
+    for header in headers.get_all("Dynamic") {
+        let value = header.value.trim();
+        if STATIC_FIELDS.iter().any(|field| field.eq_ignore_ascii_case(value)) {
+            violations.push(violation(Severity::Error, header, format!("must not list {}", value)));
+        }
+        else if !FIELDS.iter().any(|field| field.name.eq_ignore_ascii_case(value)) {
+            violations.push(violation(Severity::Error,
+                                      header,
+                                      format!("lists {}, which is not a core metadata field", value)));
+        }
+    }
+
+    if version >= (2, 3) {
+        for header in headers.get_all("Provides-Extra") {
+            let extra = header.value.trim();
+            if normalize(extra) != extra {
+                violations.push(violation(Severity::Error,
+                                          header,
+                                          format!("must be normalized to {} since Metadata-Version 2.3", normalize(extra))));
+            }
+        }
+    }
+
+    if let (Some(_), Some(header)) = (headers.get("License"), headers.get("License-Expression")) {
+        violations.push(violation(Severity::Error, header, "must not be used together with License".to_string()));
+    }
+}
+
+/// What is wrong with the value of a field that `parse_metadata` left out, without the document the error points into
+fn invalid_value_reason(error: &Error) -> String {
+    match error {
+        Error::InvalidVersion { input, span } => format!("has the invalid version {}", &input[span.clone()]),
+        Error::NumericOverflow { input, span } => {
+            format!("has the version number {}, which is too large", &input[span.clone()])
+        }
+        Error::MalformedHeader { .. } => "must be of the form `label, url`".to_string(),
+        Error::InvalidSpecifier { reason, .. }
+        | Error::InvalidRequirement { reason, .. }
+        | Error::InvalidMarker { reason, .. }
+        | Error::InvalidName { reason, .. } => format!("is invalid: {}", reason),
+        error => error.to_string(),
+    }
+}
+
+/// Reporting the fields that `parse_metadata` could not parse and left out
+fn check_invalid_fields(headers: &Headers, invalid_fields: &[Error], violations: &mut Vec<Violation>) {
+    for error in invalid_fields {
+        let start = error.span().map_or(0, |span| span.start);
+        if let Some(header) = headers.iter()
+                                     .find(|header| (header.span.start..=header.span.end).contains(&start))
+        {
+            violations.push(violation(Severity::Error, header, invalid_value_reason(error)));
+        }
+    }
+}
+
+/// Validating a metadata document against the rules of the version its `Metadata-Version` declares
+///
+/// Args:
+/// - `metadata_string` (String): The contents of a METADATA or PKG-INFO file
+///
+/// Returns `Result<Report, Error>`: Every violation of the specification, or the error of `parse_metadata` if the
+/// document cannot be read at all, e.g. `Error::UnsupportedMetadataVersion` for an unknown major version
+pub fn validate_metadata(metadata_string: String) -> Result<Report, Error> {
+    let metadata = parse_metadata(metadata_string.clone())?;
+    let metadata_version = metadata.metadata_version;
+    let headers = parse_headers(metadata_string)?;
+    let version = (metadata_version.release[0], metadata_version.release.get(1).copied().unwrap_or(0));
+
+    let mut violations = Vec::new();
+    check_metadata_version(&headers, version, &mut violations);
+    check_fields(&headers, version, &mut violations);
+    check_rules(&headers, version, &mut violations);
+    check_invalid_fields(&headers, &metadata.invalid_fields, &mut violations);
+    violations.sort_by_key(|violation| violation.span.start);
+    Ok(Report { metadata_version,
+                violations })
+}
@@ -0,0 +1,172 @@
+//! The regex based version parser `parse_version` used before the hand-written one, kept as a reference for equivalence
+//! tests and benchmarks
+
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use either::Either;
+use lazy_static::lazy_static;
+use regex::{Match, Regex};
+use rip::{Error, LocalVersion, PreReleaseType, Version};
+
+fn parse_number(input: &str, span: Range<usize>) -> Result<u64, Error> {
+    input[span.clone()].parse::<u64>()
+                       .map_err(|_| Error::NumericOverflow { input: input.to_string(),
+                                                             span })
+}
+
+fn parse_implicit_number(input: &str, label: Option<Match>, number: Option<Match>) -> Result<Option<u64>, Error> {
+    match (label, number) {
+        (_, Some(number)) => Ok(Some(parse_number(input, number.range())?)),
+        (Some(_), None) => Ok(Some(0)),
+        (None, None) => Ok(None),
+    }
+}
+
+fn parse_local_segments(input: &str, span: Range<usize>) -> Result<LocalVersion, Error> {
+    let mut parsed = Vec::new();
+    let mut offset = span.start;
+    for segment in input[span.clone()].split(['.', '-', '_']) {
+        if segment.bytes().all(|byte| byte.is_ascii_digit()) {
+            parsed.push(Either::Right(parse_number(input, offset..offset + segment.len())?));
+        }
+        else {
+            parsed.push(Either::Left(segment.to_lowercase()));
+        }
+        offset += segment.len() + 1;
+    }
+    Ok(LocalVersion { parsed,
+                      local_version_string: input[span].to_string() })
+}
+
+pub fn regex_parse_version(version_string: String) -> Result<Version, Error> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"(?i)^\s*v?(?:(?:(?P<epoch>[0-9]+)!)?(?P<release>[0-9]+(?:\.[0-9]+)*)(?P<pre>[-_\.]?(?P<pre_l>(a|b|c|rc|alpha|beta|pre|preview))[-_\.]?(?P<pre_n>[0-9]+)?)?(?P<post>(?:-(?P<post_n1>[0-9]+))|(?:[-_\.]?(?P<post_l>post|rev|r)[-_\.]?(?P<post_n2>[0-9]+)?))?(?P<dev>[-_\.]?(?P<dev_l>dev)[-_\.]?(?P<dev_n>[0-9]+)?)?)(?:\+(?P<local>[a-z0-9]+(?:[-_\.][a-z0-9]+)*))?\s*$"
+        ).unwrap();
+    }
+
+    let captures = match RE.captures(&version_string) {
+        Some(captures) => captures,
+        None => {
+            return Err(Error::InvalidVersion { span: 0..version_string.len(),
+                                               input: version_string })
+        }
+    };
+
+    let epoch = match captures.name("epoch") {
+        Some(epoch) => Some(parse_number(&version_string, epoch.range())?),
+        None => None,
+    };
+
+    let release_match = captures.name("release").unwrap();
+    let mut release = Vec::new();
+    let mut offset = release_match.start();
+    for segment in release_match.as_str().split('.') {
+        release.push(parse_number(&version_string, offset..offset + segment.len())?);
+        offset += segment.len() + 1;
+    }
+
+    let pre = match captures.name("pre_l") {
+        Some(pre_l) => {
+            let pre_type = match pre_l.as_str().to_lowercase().as_str() {
+                "a" | "alpha" => PreReleaseType::Alpha,
+                "b" | "beta" => PreReleaseType::Beta,
+                _ => PreReleaseType::Rc,
+            };
+            let pre_n = parse_implicit_number(&version_string, Some(pre_l), captures.name("pre_n"))?;
+            pre_n.map(|pre_n| (pre_type, pre_n))
+        }
+        None => None,
+    };
+
+    let post = match captures.name("post_n1") {
+        Some(post_n1) => Some(parse_number(&version_string, post_n1.range())?),
+        None => parse_implicit_number(&version_string, captures.name("post_l"), captures.name("post_n2"))?,
+    };
+    let dev = parse_implicit_number(&version_string, captures.name("dev_l"), captures.name("dev_n"))?;
+    let local = match captures.name("local") {
+        Some(local) => Some(parse_local_segments(&version_string, local.range())?),
+        None => None,
+    };
+
+    Ok(Version { epoch,
+                 release: release.into(),
+                 pre,
+                 post,
+                 dev,
+                 local,
+                 version_string })
+}
+
+/// Comparing versions through the full PEP 440 key only, without the packed fast path
+pub fn key_cmp(this: &Version, other: &Version) -> Ordering {
+    type Key<'a> = (u64, &'a [u64], (u8, Option<(&'a PreReleaseType, u64)>), Option<u64>, (bool, Option<u64>));
+
+    fn key(version: &Version) -> Key<'_> {
+        let mut release = version.release.as_slice();
+        while let [rest @ .., 0] = release {
+            release = rest;
+        }
+        let pre = match (&version.pre, version.post, version.dev) {
+            (Some((pre_type, pre_n)), _, _) => (1, Some((pre_type, *pre_n))),
+            (None, None, Some(_)) => (0, None),
+            (None, _, _) => (2, None),
+        };
+        (version.epoch.unwrap_or(0), release, pre, version.post, (version.dev.is_none(), version.dev))
+    }
+    key(this).cmp(&key(other)).then_with(|| this.local.cmp(&other.local))
+}
+
+/// Building a deterministic mix of valid, overflowing and malformed version strings
+pub fn version_corpus(size: usize) -> Vec<String> {
+    const PREFIXES: [&str; 5] = ["", "", " ", "v", "V"];
+    const EPOCHS: [&str; 5] = ["", "", "", "1!", "99999999999999999999!"];
+    const RELEASES: [&str; 8] = ["0",
+                                 "1.0",
+                                 "1.2.3",
+                                 "2023.10.15",
+                                 "20231015123045",
+                                 "1.0.0.0.0",
+                                 "01.02",
+                                 "1."];
+    const PRE: [&str; 14] = ["",
+                             "",
+                             "",
+                             "a1",
+                             "b",
+                             "-rc.2",
+                             "_alpha_3",
+                             ".beta",
+                             "c4",
+                             "PRE5",
+                             "preview",
+                             "a-",
+                             "rc99999999999999999999",
+                             "a.."];
+    const POST: [&str; 10] = ["", "", "", "-1", ".post2", "post", "_rev-3", "-r", "-", "--1"];
+    const DEV: [&str; 7] = ["", "", "", ".dev", "dev4", "-DEV_5", ".dev."];
+    const LOCAL: [&str; 8] = ["", "", "", "+ubuntu-1", "+abc.10", "+Cu118_7", "+", "+a..b"];
+    const SUFFIXES: [&str; 6] = ["", "", "", " ", "x", "\t"];
+
+    let mut state: u64 = 0x2545F4914F6CDD1D;
+    let mut pick = |choices: &[&'static str]| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        choices[(state % choices.len() as u64) as usize]
+    };
+    (0..size).map(|_| {
+                 [pick(&PREFIXES),
+                  pick(&EPOCHS),
+                  pick(&RELEASES),
+                  pick(&PRE),
+                  pick(&POST),
+                  pick(&DEV),
+                  pick(&LOCAL),
+                  pick(&SUFFIXES)].concat()
+             })
+             .collect()
+}
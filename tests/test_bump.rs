@@ -0,0 +1,188 @@
+mod version_bump {
+    use rip::bump::{bump, bump_dev, bump_major, bump_micro, bump_minor, bump_post, bump_pre, with_local, Bump};
+    use rip::PreReleaseType;
+
+    fn parse(version: &str) -> rip::Version {
+        rip::parse_version(version.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_bump_release_resets_lower_parts() {
+        assert_eq!(bump_major(&parse("1.2.3")).unwrap().to_string(), "2.0.0");
+        assert_eq!(bump_minor(&parse("1.2.3.post4+local")).unwrap().to_string(), "1.3.0");
+        assert_eq!(bump_micro(&parse("1!1.2.3.post1")).unwrap().to_string(), "1!1.2.4");
+        assert_eq!(bump_micro(&parse("1")).unwrap().to_string(), "1.0.1");
+    }
+
+    #[test]
+    fn test_bump_release_finalizes_pre_release() {
+        assert_eq!(bump_minor(&parse("1.2.0rc1")).unwrap().to_string(), "1.2.0");
+        assert_eq!(bump_major(&parse("2.0.dev3")).unwrap().to_string(), "2.0");
+        assert_eq!(bump_major(&parse("2.0.post1.dev3")).unwrap().to_string(), "3.0");
+    }
+
+    #[test]
+    fn test_bump_pre_release() {
+        assert_eq!(bump_pre(&parse("1.0a1"), PreReleaseType::Alpha).unwrap().to_string(), "1.0a2");
+        assert_eq!(bump_pre(&parse("1.0a2"), PreReleaseType::Rc).unwrap().to_string(), "1.0rc1");
+        assert_eq!(bump_pre(&parse("1.0rc1.dev2"), PreReleaseType::Rc).unwrap().to_string(),
+                   "1.0rc1");
+        assert_eq!(bump_pre(&parse("1.1.dev0"), PreReleaseType::Beta).unwrap().to_string(),
+                   "1.1b1");
+    }
+
+    #[test]
+    fn test_bump_release_then_start_pre_release() {
+        assert_eq!(bump(&parse("1.0"), &[Bump::Minor, Bump::Pre(PreReleaseType::Rc)]).unwrap()
+                                                                                     .to_string(),
+                   "1.1rc1");
+        assert_eq!(bump(&parse("1.0+local"),
+                        &[Bump::Major, Bump::Pre(PreReleaseType::Alpha), Bump::Dev]).unwrap()
+                                                                                    .to_string(),
+                   "2.0a1.dev0");
+        assert_eq!(bump(&parse("1.2.0rc1"), &[Bump::Minor, Bump::Pre(PreReleaseType::Alpha)]).unwrap()
+                                                                                             .to_string(),
+                   "1.3.0a1");
+        assert_eq!(bump(&parse("1.0"), &[Bump::Post, Bump::Dev]).unwrap().to_string(),
+                   "1.0.post1.dev0");
+        assert_eq!(bump(&parse("1.0rc1.dev2"), &[Bump::Pre(PreReleaseType::Rc), Bump::Dev]).unwrap()
+                                                                                           .to_string(),
+                   "1.0rc2.dev0");
+        assert!(bump(&parse("1.0"), &[Bump::Pre(PreReleaseType::Alpha)]).is_err());
+    }
+
+    #[test]
+    fn test_bump_pre_release_never_goes_back() {
+        assert!(matches!(bump_pre(&parse("1.0rc1"), PreReleaseType::Beta),
+                         Err(rip::Error::InvalidBump { .. })));
+        assert!(matches!(bump_pre(&parse("1.0"), PreReleaseType::Rc),
+                         Err(rip::Error::InvalidBump { .. })));
+        assert!(matches!(bump_pre(&parse("1.0.post1"), PreReleaseType::Alpha),
+                         Err(rip::Error::InvalidBump { .. })));
+        assert_eq!(bump_pre(&parse("1.0rc1"), PreReleaseType::Alpha).unwrap_err().to_string(),
+                   "cannot bump 1.0rc1: a is an earlier pre-release series than rc");
+    }
+
+    #[test]
+    fn test_bump_overflow() {
+        let max = u64::MAX;
+        for (version, part) in [(format!("{}", max), Bump::Major),
+                                (format!("1.{}", max), Bump::Minor),
+                                (format!("1.0a{}", max), Bump::Pre(PreReleaseType::Alpha)),
+                                (format!("1.0.post{}", max), Bump::Post),
+                                (format!("1.0.dev{}", max), Bump::Dev)]
+        {
+            assert!(matches!(bump(&parse(&version), &[part]), Err(rip::Error::InvalidBump { .. })),
+                    "{}",
+                    version);
+        }
+    }
+
+    #[test]
+    fn test_bump_post_and_dev() {
+        assert_eq!(bump_post(&parse("1.0")).unwrap().to_string(), "1.0.post1");
+        assert_eq!(bump_post(&parse("1.0b1.post1")).unwrap().to_string(), "1.0b1.post2");
+        assert_eq!(bump_post(&parse("1.0.post2.dev0")).unwrap().to_string(), "1.0.post2");
+        assert!(matches!(bump_dev(&parse("1.1")), Err(rip::Error::InvalidBump { .. })));
+        assert_eq!(bump_dev(&parse("1.1.dev0")).unwrap().to_string(), "1.1.dev1");
+        assert_eq!(bump_dev(&parse("1.1a1.dev3+local")).unwrap().to_string(), "1.1a1.dev4");
+    }
+
+    #[test]
+    fn test_bumped_versions_are_newer() {
+        for (version, part) in [("1.2.3", Bump::Major),
+                                ("1.2.3rc1", Bump::Micro),
+                                ("1.0a1", Bump::Pre(PreReleaseType::Beta)),
+                                ("1.0.post1.dev1", Bump::Post),
+                                ("1.0.dev1", Bump::Dev)]
+        {
+            let version = parse(version);
+            let bumped = bump(&version, &[part]).unwrap();
+            assert!(bumped > version, "{} should be newer than {}", bumped, version);
+            assert_eq!(bumped.version_string, bumped.to_string());
+        }
+    }
+
+    #[test]
+    fn test_every_bump_is_newer() {
+        let parts = [Bump::Major,
+                     Bump::Minor,
+                     Bump::Micro,
+                     Bump::Pre(PreReleaseType::Alpha),
+                     Bump::Pre(PreReleaseType::Beta),
+                     Bump::Pre(PreReleaseType::Rc),
+                     Bump::Post,
+                     Bump::Dev];
+        let mut combinations: Vec<Vec<Bump>> = parts.iter().map(|part| vec![part.clone()]).collect();
+        for length in 2..=3 {
+            let shorter = combinations.iter()
+                                      .filter(|combination| combination.len() == length - 1)
+                                      .cloned()
+                                      .collect::<Vec<_>>();
+            for combination in shorter {
+                for part in &parts {
+                    let mut longer = combination.clone();
+                    longer.push(part.clone());
+                    combinations.push(longer);
+                }
+            }
+        }
+        for version in ["1.2.3",
+                        "1.2.0rc1",
+                        "1.0a1",
+                        "2.0.dev3",
+                        "1.0.dev1",
+                        "1.0rc1.dev2",
+                        "1.0.post1",
+                        "1.0.post1.dev1",
+                        "1!1.0b2.post1.dev0",
+                        "1.0+local"]
+        {
+            let version = parse(version);
+            for combination in &combinations {
+                if let Ok(bumped) = bump(&version, combination) {
+                    assert!(bumped > version, "{:?} of {} gives {}", combination, version, bumped);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_replace_and_strip_local() {
+        assert_eq!(with_local(&parse("1.0+cu118"), "CU-121").unwrap().to_string(), "1.0+cu.121");
+        assert_eq!(with_local(&parse("1.0"), "ubuntu1").unwrap().to_string(), "1.0+ubuntu1");
+        assert!(with_local(&parse("1.0"), "not valid").is_err());
+        assert_eq!(parse("1.0+cu118").public().to_string(), "1.0");
+    }
+}
+
+mod version_bump_cli {
+    use std::process::Command;
+
+    fn rip(args: &[&str]) -> (bool, String) {
+        let output = Command::new(env!("CARGO_BIN_EXE_rip")).args(args).output().unwrap();
+        (output.status.success(), String::from_utf8(output.stdout).unwrap())
+    }
+
+    #[test]
+    fn test_cli_bump() {
+        assert_eq!(rip(&["version", "bump", "1.2.3", "minor"]), (true, "1.3.0\n".to_string()));
+        assert_eq!(rip(&["version", "bump", "1.2.3", "minor", "beta"]),
+                   (true, "1.3.0b1\n".to_string()));
+        assert_eq!(rip(&["version", "bump", "1.0a1", "rc", "--local", "cu118"]),
+                   (true, "1.0rc1+cu118\n".to_string()));
+        assert_eq!(rip(&["version", "bump", "1.2.0rc1", "minor", "alpha"]),
+                   (true, "1.3.0a1\n".to_string()));
+        assert_eq!(rip(&["version", "bump", "1.0+cu118", "--strip-local"]),
+                   (true, "1.0\n".to_string()));
+    }
+
+    #[test]
+    fn test_cli_bump_errors() {
+        assert!(!rip(&["version", "bump", "1.0rc1", "alpha"]).0);
+        assert!(!rip(&["version", "bump", "not-a-version", "major"]).0);
+        assert!(!rip(&["version", "bump", "1.0"]).0);
+        assert!(!rip(&["version", "bump", "18446744073709551615", "major"]).0);
+        assert!(!rip(&["version", "bump", "1.0a18446744073709551615", "alpha"]).0);
+    }
+}
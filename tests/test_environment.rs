@@ -0,0 +1,126 @@
+#[cfg(feature = "environment")]
+mod marker_environment {
+    use std::fs;
+
+    use rip::environment::{parse_marker_environment_json, parse_marker_environment_toml};
+    use rip::marker::{MarkerEnvironment, MarkerTree};
+
+    const AARCH64_JSON: &str = r##"{
+        "implementation_name": "cpython",
+        "implementation_version": "3.8.10",
+        "os_name": "posix",
+        "platform_machine": "aarch64",
+        "platform_python_implementation": "CPython",
+        "platform_release": "5.10.0",
+        "platform_system": "Linux",
+        "platform_version": "#1 SMP \"Debian\" é 😀",
+        "python_full_version": "3.8.10",
+        "python_version": "3.8",
+        "sys_platform": "linux"
+    }"##;
+
+    const AARCH64_TOML: &str = r##"# Raspberry Pi 4
+implementation_name = "cpython"
+implementation_version = "3.8.10"
+os_name = 'posix'
+platform_machine = "aarch64"   # 64-bit
+platform_python_implementation = "CPython"
+platform_release = "5.10.0"
+platform_system = "Linux"
+"platform_version" = "#1 SMP \"Debian\" é \U0001F600"
+python_full_version = "3.8.10"
+python_version = "3.8"
+sys_platform = "linux"
+"##;
+
+    #[test]
+    fn test_parse_json_and_toml() {
+        let environment = parse_marker_environment_json(AARCH64_JSON.to_string()).unwrap();
+        assert_eq!(environment.platform_machine, "aarch64");
+        assert_eq!(environment.python_full_version, "3.8.10");
+        assert_eq!(environment.platform_version, "#1 SMP \"Debian\" é 😀");
+        assert_eq!(parse_marker_environment_toml(AARCH64_TOML.to_string()).unwrap(), environment);
+
+        let marker: MarkerTree = "platform_machine == 'aarch64' and python_version < '3.9'".parse().unwrap();
+        assert!(marker.evaluate(&environment, &[]));
+    }
+
+    #[test]
+    fn test_parse_extra_keys() {
+        let environment = parse_marker_environment_json(AARCH64_JSON.to_string()).unwrap();
+        let extended = AARCH64_JSON.replace("\"sys_platform\": \"linux\"",
+                                            "\"sys_platform\": \"linux\", \"extra\": \"test\"");
+        assert_eq!(parse_marker_environment_json(extended).unwrap(), environment);
+
+        let extended = format!("{}\n[target]\nname = \"raspberry-pi\"\n", AARCH64_TOML);
+        assert_eq!(parse_marker_environment_toml(extended).unwrap(), environment);
+        let multiline = AARCH64_TOML.replace("platform_release = \"5.10.0\"", "platform_release = \"\"\"\n5.10.0\"\"\"");
+        assert_eq!(parse_marker_environment_toml(multiline).unwrap(), environment);
+        let dotted = format!("{}target.name = \"raspberry-pi\"\n", AARCH64_TOML);
+        assert_eq!(parse_marker_environment_toml(dotted).unwrap(), environment);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let missing = AARCH64_JSON.replace(",\n        \"sys_platform\": \"linux\"", "");
+        assert!(matches!(parse_marker_environment_json(missing),
+                         Err(rip::Error::InvalidMarkerEnvironment { reason, .. }) if reason == "missing field `sys_platform`"));
+        let missing = AARCH64_TOML.replace("sys_platform = \"linux\"\n", "");
+        assert!(matches!(parse_marker_environment_toml(missing),
+                         Err(rip::Error::InvalidMarkerEnvironment { reason, .. }) if reason == "missing field `sys_platform`"));
+        let twice = AARCH64_JSON.replace("\"os_name\": \"posix\"", "\"os_name\": \"posix\", \"os_name\": \"nt\"");
+        assert!(matches!(parse_marker_environment_json(twice),
+                         Err(rip::Error::InvalidMarkerEnvironment { reason, .. }) if reason == "duplicate field `os_name`"));
+        let twice = AARCH64_TOML.replace("os_name = 'posix'", "os_name = 'posix'\nos_name = 'nt'");
+        let start = twice.find("os_name = 'nt'").unwrap();
+        assert!(matches!(parse_marker_environment_toml(twice),
+                         Err(rip::Error::InvalidMarkerEnvironment { span, .. }) if span == (start..start + 7)));
+
+        assert_eq!(parse_marker_environment_json(r#"{"os_name": 1}"#.to_string()),
+                   Err(rip::Error::InvalidMarkerEnvironment { input: r#"{"os_name": 1}"#.to_string(),
+                                                              span: 12..13,
+                                                              reason:
+                                                                  "invalid type: integer `1`, expected a string".to_string() }));
+        assert!(matches!(parse_marker_environment_json(r#"{"os_name": "posix" "#.to_string()),
+                         Err(rip::Error::InvalidMarkerEnvironment { span, .. }) if span == (20..20)));
+        assert!(matches!(parse_marker_environment_json("{\n  \"os_name\": \"\\x\"}".to_string()),
+                         Err(rip::Error::InvalidMarkerEnvironment { span, .. }) if span == (17..18)));
+        assert!(matches!(parse_marker_environment_toml("os_name = \"posix\" extra\n".to_string()),
+                         Err(rip::Error::InvalidMarkerEnvironment { span, .. }) if span == (18..18)));
+    }
+
+    #[test]
+    fn test_from_file() {
+        let directory = std::env::temp_dir().join(format!("rip-marker-environment-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("aarch64.json"), AARCH64_JSON).unwrap();
+        fs::write(directory.join("aarch64.toml"), AARCH64_TOML).unwrap();
+
+        let environment = MarkerEnvironment::from_file(directory.join("aarch64.json")).unwrap();
+        assert_eq!(MarkerEnvironment::from_file(directory.join("aarch64.toml")).unwrap(),
+                   environment);
+        assert!(matches!(MarkerEnvironment::from_file(directory.join("missing.json")),
+                         Err(rip::Error::UnreadableFile { .. })));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
+
+mod interpreter_environment {
+    use std::process::Command;
+
+    use rip::marker::MarkerEnvironment;
+
+    #[test]
+    fn test_from_interpreter() {
+        assert!(matches!(MarkerEnvironment::from_interpreter("rip-no-such-python"),
+                         Err(rip::Error::InterpreterFailed { .. })));
+        // Only checked where a Python interpreter is installed
+        if Command::new("python3").arg("--version").output().is_err() {
+            return;
+        }
+        let environment = MarkerEnvironment::from_interpreter("python3").unwrap();
+        assert!(environment.python_full_version.starts_with(&environment.python_version));
+        assert!(rip::parse_version(environment.python_full_version.clone()).is_ok());
+        assert!(!environment.sys_platform.is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+mod header_parse {
+    use rip::header::parse_headers;
+    use rip::Error;
+
+    #[test]
+    fn test_fields_in_order() {
+        let headers = parse_headers("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\n".to_string()).unwrap();
+        let fields = headers.iter()
+                            .map(|header| (header.name.as_str(), header.value.as_str()))
+                            .collect::<Vec<_>>();
+        assert_eq!(fields, [("Metadata-Version", "2.1"), ("Name", "rip"), ("Version", "1.0")]);
+        assert_eq!(headers.body(), None);
+    }
+
+    #[test]
+    fn test_case_insensitive_lookup() {
+        let headers = parse_headers("name: rip\nREQUIRES-DIST: numpy\n".to_string()).unwrap();
+        assert_eq!(headers.get("Name").unwrap().value, "rip");
+        assert_eq!(headers.get("requires-dist").unwrap().name, "REQUIRES-DIST");
+        assert!(headers.get("Version").is_none());
+    }
+
+    #[test]
+    fn test_field_names_match_exactly() {
+        let headers = parse_headers("Name-X: other\nName: rip\n".to_string()).unwrap();
+        assert_eq!(headers.get("Name").unwrap().value, "rip");
+        assert_eq!(rip::get_metadata_attribute("Name-X: other\nName: rip\n", "Name:").unwrap(),
+                   "rip");
+    }
+
+    #[test]
+    fn test_values_keep_colons() {
+        let headers =
+            parse_headers("Summary: Parsing: the hard part\nProject-URL: Source, https://example.com/rip\n".to_string()).unwrap();
+        assert_eq!(headers.get("Summary").unwrap().value, "Parsing: the hard part");
+        assert_eq!(headers.get("Project-URL").unwrap().value, "Source, https://example.com/rip");
+    }
+
+    #[test]
+    fn test_repeated_fields() {
+        let headers = parse_headers("Classifier: Programming Language :: Rust\nName: rip\n\
+                                     Classifier: License :: OSI Approved :: MIT License\n"
+                                                                                          .to_string()).unwrap();
+        let classifiers = headers.get_all("classifier")
+                                 .map(|header| header.value.as_str())
+                                 .collect::<Vec<_>>();
+        assert_eq!(classifiers,
+                   ["Programming Language :: Rust", "License :: OSI Approved :: MIT License"]);
+    }
+
+    #[test]
+    fn test_folded_values() {
+        let source = "Summary: a very\n  long summary\n\tindeed\nName: rip\n";
+        let headers = parse_headers(source.to_string()).unwrap();
+        let summary = headers.get("Summary").unwrap();
+        assert_eq!(summary.value, "a very\n  long summary\n\tindeed");
+        assert_eq!(summary.span, 0..38);
+        assert_eq!(&source[summary.value_span.clone()], summary.value);
+        assert_eq!(headers.get("Name").unwrap().value, "rip");
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        let headers = parse_headers("Name: rip\r\nVersion: 1.0\r\n\r\nBody\r\n".to_string()).unwrap();
+        assert_eq!(headers.get("Name").unwrap().value, "rip");
+        assert_eq!(headers.get("Version").unwrap().value_span, 20..23);
+        assert_eq!(headers.body(), Some("Body\r\n"));
+    }
+
+    #[test]
+    fn test_body() {
+        let headers = parse_headers("Name: rip\n\n# rip\n\nNot: a header\n".to_string()).unwrap();
+        assert_eq!(headers.iter().count(), 1);
+        assert_eq!(headers.body(), Some("# rip\n\nNot: a header\n"));
+
+        let headers = parse_headers("Name: rip\n\n".to_string()).unwrap();
+        assert_eq!(headers.body(), Some(""));
+    }
+
+    #[test]
+    fn test_empty_values() {
+        let headers = parse_headers("Keywords:\nLicense: \n".to_string()).unwrap();
+        assert_eq!(headers.get("Keywords").unwrap().value, "");
+        assert_eq!(headers.get("License").unwrap().value_span, 19..19);
+    }
+
+    #[test]
+    fn test_malformed_lines() {
+        let source = "Name: rip\nnot a header\n".to_string();
+        assert_eq!(parse_headers(source.clone()).unwrap_err(),
+                   Error::MalformedHeader { input: source,
+                                            span: 10..22 });
+        assert!(matches!(parse_headers(" continued\nName: rip\n".to_string()),
+                         Err(Error::MalformedHeader { span, .. }) if span == (0..10)));
+        assert!(matches!(parse_headers(": rip\n".to_string()),
+                         Err(Error::MalformedHeader { span, .. }) if span == (0..5)));
+        assert!(matches!(parse_headers("Home page: https://example.com\n".to_string()),
+                         Err(Error::MalformedHeader { span, .. }) if span == (0..30)));
+    }
+}
+
+mod metadata_headers {
+    use rip::parse_metadata;
+
+    #[test]
+    fn test_metadata_field_names_ignore_case() {
+        let metadata = parse_metadata("metadata-version: 2.1\nNAME: rip\nversion: 1.0\n".to_string()).unwrap();
+        assert_eq!(metadata.name.as_str(), "rip");
+        assert_eq!(metadata.version.to_string(), "1.0");
+    }
+
+    #[test]
+    fn test_metadata_requires_dist_after_body_is_ignored() {
+        let metadata = parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nRequires-Dist: numpy\n\n\
+                                       Requires-Dist: not-a-dependency\n"
+                                                                         .to_string()).unwrap();
+        assert_eq!(metadata.requires_dist.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_metadata_folded_requires_dist() {
+        let metadata = parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nRequires-Dist: numpy\n  >=1.21\n"
+                                                                                                        .to_string()).unwrap();
+        assert_eq!(metadata.requires_dist.unwrap()[0].to_string(), "numpy>=1.21");
+    }
+}
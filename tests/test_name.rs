@@ -0,0 +1,76 @@
+mod name_normalize {
+    use std::collections::HashSet;
+
+    use rip::name::{normalize, ExtraName, PackageName};
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Foo_Bar"), "foo-bar");
+        assert_eq!(normalize("FOO.bar"), "foo-bar");
+        assert_eq!(normalize("foo-._bar"), "foo-bar");
+        assert_eq!(normalize("zope.interface"), "zope-interface");
+    }
+
+    #[test]
+    fn test_package_names_compare_normalized() {
+        let names = ["Foo_Bar", "foo-bar", "FOO.bar"].map(|name| name.parse::<PackageName>().unwrap());
+        assert_eq!(names[0], names[1]);
+        assert_eq!(names[1], names[2]);
+        assert_eq!(names.iter().collect::<HashSet<_>>().len(), 1);
+        assert_ne!(names[0], "foobar".parse().unwrap());
+        assert!("ABC".parse::<PackageName>().unwrap() < "abd".parse().unwrap());
+    }
+
+    #[test]
+    fn test_names_keep_their_spelling() {
+        let name: PackageName = "Foo_Bar".parse().unwrap();
+        assert_eq!(name.to_string(), "Foo_Bar");
+        assert_eq!(name.as_str(), "Foo_Bar");
+        assert_eq!(name.normalized(), "foo-bar");
+
+        let extra: ExtraName = "Dev_Tools".parse().unwrap();
+        assert_eq!(extra.to_string(), "Dev_Tools");
+        assert_eq!(extra, "dev-tools".parse().unwrap());
+    }
+
+    #[test]
+    fn test_metadata_name() {
+        let metadata = rip::parse_metadata("Metadata-Version: 2.1\nName: Flask_SQLAlchemy\nVersion: 3.0".to_string()).unwrap();
+        assert_eq!(metadata.name, "flask-sqlalchemy".parse().unwrap());
+        assert_eq!(metadata.name.as_str(), "Flask_SQLAlchemy");
+    }
+}
+
+mod name_validate {
+    use rip::name::{parse_extra_name, parse_package_name};
+
+    #[test]
+    fn test_valid_names() {
+        for name in ["a", "A1", "zope.interface", "foo-_.bar", "1password"] {
+            assert!(parse_package_name(name.to_string()).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        assert_eq!(parse_package_name("".to_string()).unwrap_err(),
+                   rip::Error::InvalidName { input: "".to_string(),
+                                             span: 0..0,
+                                             reason: "a package name must not be empty".to_string() });
+        assert!(matches!(parse_package_name("foo bar".to_string()),
+                         Err(rip::Error::InvalidName { span, .. }) if span == (3..4)));
+        assert!(matches!(parse_package_name("naïve".to_string()),
+                         Err(rip::Error::InvalidName { span, .. }) if span == (2..4)));
+        assert!(matches!(parse_extra_name("-dev".to_string()),
+                         Err(rip::Error::InvalidName { span, reason, .. })
+                             if span == (0..4) && reason == "an extra name must start and end with a letter or digit"));
+        assert!(matches!(parse_extra_name("dev.".to_string()), Err(rip::Error::InvalidName { .. })));
+    }
+
+    #[test]
+    fn test_invalid_metadata_name() {
+        let metadata_string = "Metadata-Version: 2.1\nName: foo bar\nVersion: 1.0".to_string();
+        assert!(matches!(rip::parse_metadata(metadata_string),
+                         Err(rip::Error::InvalidName { span, .. }) if span == (31..32)));
+    }
+}
@@ -1,40 +1,217 @@
 #[cfg(test)]
 mod metadata_parse {
-    use rip;
-
     #[test]
     fn test_parse_metadata() {
         let metadata = rip::parse_metadata("Metadata-Version: 2.1\nName: pandas\nVersion: 1.5.3".to_string());
         assert!(metadata.as_ref().is_ok());
         assert_eq!(metadata.as_ref().unwrap().version,
-                   rip::Version { epoch: None,
-                                  release: [1, 5, 3].to_vec(),
-                                  pre: None,
-                                  post: None,
-                                  dev: None,
-                                  local: None,
-                                  version_string: "1.5.3".to_string() });
+                   rip::AnyVersion::Pep440(rip::Version { epoch: None,
+                                                          release: rip::Release::from_slice(&[1, 5, 3]),
+                                                          pre: None,
+                                                          post: None,
+                                                          dev: None,
+                                                          local: None,
+                                                          version_string: "1.5.3".to_string() }));
         assert_eq!(metadata.as_ref().unwrap().metadata_version,
                    rip::Version { epoch: None,
-                                  release: [2, 1].to_vec(),
+                                  release: rip::Release::from_slice(&[2, 1]),
                                   pre: None,
                                   post: None,
                                   dev: None,
                                   local: None,
                                   version_string: "2.1".to_string() });
 
-        assert_eq!(metadata.as_ref().unwrap().name, "pandas".to_string());
+        assert_eq!(metadata.as_ref().unwrap().name.as_str(), "pandas");
+    }
+
+    #[test]
+    fn test_parse_metadata_legacy_version() {
+        let metadata = rip::parse_metadata("Metadata-Version: 1.0\nName: oldpkg\nVersion: 1.0-SNAPSHOT".to_string()).unwrap();
+        assert!(metadata.version.is_legacy());
+        assert_eq!(metadata.version.to_string(), "1.0-SNAPSHOT");
+    }
+
+    const PIP_METADATA: &str = "Metadata-Version: 2.2
+Name: pip
+Version: 23.0
+Dynamic: Requires-Dist
+Summary: The PyPA recommended tool for installing Python packages.
+Home-page: https://pip.pypa.io/
+Download-URL: https://pypi.org/project/pip/#files
+Author: The pip developers
+Author-email: distutils-sig@python.org
+Maintainer: pip maintainers
+Maintainer-email: pip@example.com
+License: MIT
+Project-URL: Documentation, https://pip.pypa.io
+Project-URL: Source, https://github.com/pypa/pip
+Keywords: packaging, installer,  pip
+Platform: any
+Supported-Platform: RedHat 7.2
+Supported-Platform: i386-win32-2791
+Classifier: Development Status :: 5 - Production/Stable
+Classifier: License :: OSI Approved :: MIT License
+Requires-Python: >=3.7
+Requires-External: libssl
+Description-Content-Type: text/x-rst
+Provides-Extra: Dev_Tools
+Requires-Dist: pytest ; extra == 'dev-tools'
+";
+
+    #[test]
+    fn test_parse_every_field() {
+        let metadata = rip::parse_metadata(PIP_METADATA.to_string()).unwrap();
+        assert_eq!(metadata.dynamic, Some(vec!["Requires-Dist".to_string()]));
+        assert_eq!(metadata.summary.as_deref(),
+                   Some("The PyPA recommended tool for installing Python packages."));
+        assert_eq!(metadata.homepage.as_deref(), Some("https://pip.pypa.io/"));
+        assert_eq!(metadata.download_url.as_deref(), Some("https://pypi.org/project/pip/#files"));
+        assert_eq!(metadata.author.as_deref(), Some("The pip developers"));
+        assert_eq!(metadata.author_email.as_deref(), Some("distutils-sig@python.org"));
+        assert_eq!(metadata.maintainer.as_deref(), Some("pip maintainers"));
+        assert_eq!(metadata.maintainer_email.as_deref(), Some("pip@example.com"));
+        assert_eq!(metadata.license.as_deref(), Some("MIT"));
+        assert_eq!(metadata.project_urls,
+                   Some(vec![rip::ProjectUrl { label: "Documentation".to_string(),
+                                               url: "https://pip.pypa.io".to_string() },
+                             rip::ProjectUrl { label: "Source".to_string(),
+                                               url: "https://github.com/pypa/pip".to_string() },]));
+        assert_eq!(metadata.keywords,
+                   Some(vec!["packaging".to_string(), "installer".to_string(), "pip".to_string()]));
+        assert_eq!(metadata.platforms, Some(vec!["any".to_string()]));
+        assert_eq!(metadata.supported_platforms,
+                   Some(vec!["RedHat 7.2".to_string(), "i386-win32-2791".to_string()]));
+        assert_eq!(metadata.classifiers.unwrap().len(), 2);
+        assert_eq!(metadata.requires_python, Some(">=3.7".parse().unwrap()));
+        assert_eq!(metadata.requires_externals, Some(vec!["libssl".to_string()]));
+        assert_eq!(metadata.description_content_type.as_deref(), Some("text/x-rst"));
+        assert_eq!(metadata.provides_extras, Some(vec!["dev-tools".parse().unwrap()]));
+        assert_eq!(metadata.requires_dist.unwrap()[0].to_string(),
+                   "pytest; extra == \"dev-tools\"");
+        assert_eq!(metadata.description, None);
+    }
+
+    #[test]
+    fn test_body_description() {
+        let metadata = rip::parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\n\
+                                            Description-Content-Type: text/markdown\n\n# rip\n\nInstalls: things\n"
+                                                                                                     .to_string()).unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("# rip\n\nInstalls: things\n"));
+        assert_eq!(metadata.description_content_type.as_deref(), Some("text/markdown"));
+    }
+
+    #[test]
+    fn test_legacy_description() {
+        let metadata = rip::parse_metadata("Metadata-Version: 1.2\nName: rip\nVersion: 1.0\nDescription: rip\n        \
+                                            ===\n        \n            indented\nLicense: MIT\n"
+                                                                                                .to_string()).unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("rip\n===\n\n    indented"));
+        assert_eq!(metadata.license.as_deref(), Some("MIT"));
+
+        let metadata = rip::parse_metadata("Metadata-Version: 2.1\r\nName: rip\r\nVersion: 1.0\r\n\
+                                            Description-Content-Type: text/x-rst\r\nDescription: rip\r\n       |===\r\n       |\r\n\
+                                            \x20      |  indented\r\n"
+                                                                      .to_string()).unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("rip\n===\n\n  indented"));
+        assert_eq!(metadata.description_content_type.as_deref(), Some("text/x-rst"));
+    }
+
+    #[test]
+    fn test_body_description_takes_precedence() {
+        let metadata =
+            rip::parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nDescription: old\n\nnew\n".to_string()).unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("new\n"));
+
+        let metadata =
+            rip::parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nDescription: old\n\n\n".to_string()).unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("old"));
+    }
+
+    #[test]
+    fn test_missing_and_empty_fields() {
+        let metadata = rip::parse_metadata("Metadata-Version: 1.0\nName: oldpkg\nVersion: 1.0\nLicense: \n\
+                                            Keywords: old  fashioned\n"
+                                                                       .to_string()).unwrap();
+        assert_eq!(metadata.license, None);
+        assert_eq!(metadata.keywords, Some(vec!["old".to_string(), "fashioned".to_string()]));
+        assert_eq!(metadata.summary, None);
+        assert_eq!(metadata.classifiers, None);
+        assert_eq!(metadata.project_urls, None);
+
+        let metadata =
+            rip::parse_metadata("Metadata-Version: 1.2\nName: rip\nVersion: 1.0\nRequires-Python: \t\n".to_string()).unwrap();
+        assert_eq!(metadata.requires_python, None);
+    }
+
+    #[test]
+    fn test_legacy_metadata_with_invalid_fields() {
+        let metadata = rip::parse_metadata("Metadata-Version: 1.2\nName: oldpkg\nVersion: 1.0-SNAPSHOT\n\
+                                            Requires-Dist: foo (>=1.0-SNAPSHOT-2)\nRequires-Dist: bar (>=2.0)\n\
+                                            Requires-Python: >= 2.4.*\nProject-URL: https://example.com\n"
+                                                                                                          .to_string()).unwrap();
+        assert!(metadata.version.is_legacy());
+        assert_eq!(metadata.requires_dist.unwrap(), ["bar>=2.0".parse().unwrap()]);
+        assert_eq!(metadata.requires_python, None);
+        assert_eq!(metadata.project_urls, None);
+        assert!(matches!(metadata.invalid_fields.as_slice(),
+                         [rip::Error::InvalidVersion { .. },
+                          rip::Error::InvalidSpecifier { .. },
+                          rip::Error::MalformedHeader { .. }]));
+    }
+
+    #[test]
+    fn test_invalid_field_error_spans() {
+        let metadata = "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nRequires-Python: >=3.x\n".to_string();
+        let offset = metadata.find("3.x").unwrap();
+        assert!(matches!(rip::parse_metadata(metadata).unwrap().invalid_fields.as_slice(),
+                         [rip::Error::InvalidVersion { span, .. }] if *span == (offset..offset + 3)));
+
+        let metadata = "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nProvides-Extra: dev tools\n".to_string();
+        let offset = metadata.find(" tools").unwrap();
+        assert!(matches!(rip::parse_metadata(metadata).unwrap().invalid_fields.as_slice(),
+                         [rip::Error::InvalidName { span, .. }] if *span == (offset..offset + 1)));
+
+        let metadata = "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nProject-URL: https://example.com\n".to_string();
+        let offset = metadata.find("Project-URL").unwrap();
+        assert!(matches!(rip::parse_metadata(metadata.clone()).unwrap().invalid_fields.as_slice(),
+                         [rip::Error::MalformedHeader { span, .. }] if *span == (offset..metadata.len() - 1)));
+
+        let metadata = "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nProject-URL: , https://example.com\n".to_string();
+        let offset = metadata.find("Project-URL").unwrap();
+        assert!(matches!(rip::parse_metadata(metadata.clone()).unwrap().invalid_fields.as_slice(),
+                         [rip::Error::MalformedHeader { span, .. }] if *span == (offset..metadata.len() - 1)));
+    }
+
+    #[test]
+    fn test_folded_invalid_field_error_spans() {
+        let metadata = "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nRequires-Dist: bar (>=1.0,\n        \
+                        <99999999999999999999999)\n"
+                                                    .to_string();
+        let offset = metadata.find("99999").unwrap();
+        assert!(matches!(rip::parse_metadata(metadata).unwrap().invalid_fields.as_slice(),
+                         [rip::Error::NumericOverflow { span, .. }] if *span == (offset..offset + 23)));
+
+        let metadata = "Metadata-Version: 2.1\r\nName: rip\r\nVersion: 1.0\r\nRequires-Dist: bar;\r\n        \
+                        python_version >=\r\n        '3.8' xor\r\n"
+                                                                   .to_string();
+        let offset = metadata.find("xor").unwrap();
+        assert!(matches!(rip::parse_metadata(metadata).unwrap().invalid_fields.as_slice(),
+                         [rip::Error::InvalidMarker { span, .. }] if *span == (offset..offset + 3)));
+
+        let metadata = "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nRequires-Python: >=3.8,\n  <3.x\n".to_string();
+        let offset = metadata.find("3.x").unwrap();
+        assert!(matches!(rip::parse_metadata(metadata).unwrap().invalid_fields.as_slice(),
+                         [rip::Error::InvalidVersion { span, .. }] if *span == (offset..offset + 3)));
     }
 }
 
 mod version_parse {
-    use rip;
 
     #[test]
     fn test_parse_release_version() {
         assert_eq!(rip::parse_version("1.5.3".to_string()),
                    Ok(rip::Version { epoch: None,
-                                     release: [1, 5, 3].to_vec(),
+                                     release: rip::Release::from_slice(&[1, 5, 3]),
                                      pre: None,
                                      post: None,
                                      dev: None,
@@ -46,7 +223,7 @@ mod version_parse {
     fn test_parse_release_version_with_epoch_and_dev() {
         assert_eq!(rip::parse_version("2!1.dev0".to_string()),
                    Ok(rip::Version { epoch: Some(2),
-                                     release: [1].to_vec(),
+                                     release: rip::Release::from_slice(&[1]),
                                      pre: None,
                                      post: None,
                                      dev: Some(0),
@@ -58,8 +235,8 @@ mod version_parse {
     fn test_parse_release_version_with_alpha() {
         assert_eq!(rip::parse_version("1.5alpha1".to_string()),
                    Ok(rip::Version { epoch: None,
-                                     release: [1, 5].to_vec(),
-                                     pre: Some((rip::PreReleaseType::Alpha, [1].to_vec())),
+                                     release: rip::Release::from_slice(&[1, 5]),
+                                     pre: Some((rip::PreReleaseType::Alpha, 1)),
                                      post: None,
                                      dev: None,
                                      local: None,
@@ -70,8 +247,8 @@ mod version_parse {
     fn test_parse_release_version_with_beta() {
         assert_eq!(rip::parse_version("1.5b2".to_string()),
                    Ok(rip::Version { epoch: None,
-                                     release: [1, 5].to_vec(),
-                                     pre: Some((rip::PreReleaseType::Beta, [2].to_vec())),
+                                     release: rip::Release::from_slice(&[1, 5]),
+                                     pre: Some((rip::PreReleaseType::Beta, 2)),
                                      post: None,
                                      dev: None,
                                      local: None,
@@ -82,8 +259,8 @@ mod version_parse {
     fn test_parse_release_version_with_rc() {
         assert_eq!(rip::parse_version("1.5rc5".to_string()),
                    Ok(rip::Version { epoch: None,
-                                     release: [1, 5].to_vec(),
-                                     pre: Some((rip::PreReleaseType::Rc, [5].to_vec())),
+                                     release: rip::Release::from_slice(&[1, 5]),
+                                     pre: Some((rip::PreReleaseType::Rc, 5)),
                                      post: None,
                                      dev: None,
                                      local: None,
@@ -94,8 +271,8 @@ mod version_parse {
     fn test_parse_release_version_with_preview() {
         assert_eq!(rip::parse_version("1.5-preview1".to_string()),
                    Ok(rip::Version { epoch: None,
-                                     release: [1, 5].to_vec(),
-                                     pre: Some((rip::PreReleaseType::Rc, [1].to_vec())),
+                                     release: rip::Release::from_slice(&[1, 5]),
+                                     pre: Some((rip::PreReleaseType::Rc, 1)),
                                      post: None,
                                      dev: None,
                                      local: None,
@@ -106,7 +283,7 @@ mod version_parse {
     fn test_parse_release_version_with_post() {
         assert_eq!(rip::parse_version("1.3.9-post12".to_string()),
                    Ok(rip::Version { epoch: None,
-                                     release: [1, 3, 9].to_vec(),
+                                     release: rip::Release::from_slice(&[1, 3, 9]),
                                      pre: None,
                                      post: Some(12),
                                      dev: None,
@@ -118,7 +295,7 @@ mod version_parse {
     fn test_parse_release_version_with_dev() {
         assert_eq!(rip::parse_version("1.dev0".to_string()),
                    Ok(rip::Version { epoch: None,
-                                     release: [1].to_vec(),
+                                     release: rip::Release::from_slice(&[1]),
                                      pre: None,
                                      post: None,
                                      dev: Some(0),
@@ -130,28 +307,174 @@ mod version_parse {
     fn test_parse_release_version_with_local() {
         assert_eq!(rip::parse_version("1.2.3+some.local.version".to_string()),
                    Ok(rip::Version { epoch: None,
-                                     release: [1, 2, 3].to_vec(),
+                                     release: rip::Release::from_slice(&[1, 2, 3]),
                                      pre: None,
                                      post: None,
                                      dev: None,
-                                     local: Some("some.local.version".to_string()),
+                                     local: Some(rip::parse_local_version("some.local.version".to_string()).unwrap()),
                                      version_string: "1.dev0".to_string() }))
     }
 }
 
+mod version_parse_spellings {
+    use rip::PreReleaseType;
+
+    fn parse(version: &str) -> rip::Version {
+        rip::parse_version(version.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_implicit_pre_release_number() {
+        assert_eq!(parse("1.0a").pre, Some((PreReleaseType::Alpha, 0)));
+        assert_eq!(parse("1.0-beta").pre, Some((PreReleaseType::Beta, 0)));
+        assert_eq!(parse("1.0.RC").pre, Some((PreReleaseType::Rc, 0)));
+        assert_eq!(parse("1.0_pre.3").pre, Some((PreReleaseType::Rc, 3)));
+    }
+
+    #[test]
+    fn test_implicit_post_release_number() {
+        assert_eq!(parse("1.0.post").post, Some(0));
+        assert_eq!(parse("1.0-r").post, Some(0));
+        assert_eq!(parse("1.0rev").post, Some(0));
+        assert_eq!(parse("1.0.POST_2").post, Some(2));
+    }
+
+    #[test]
+    fn test_dash_number_post_release() {
+        assert_eq!(parse("1.0-1").post, Some(1));
+        assert_eq!(parse("1.0b2-3").post, Some(3));
+        assert_eq!(parse("1.0-1").to_string(), "1.0.post1");
+    }
+
+    #[test]
+    fn test_implicit_dev_release_number() {
+        assert_eq!(parse("1.0.dev").dev, Some(0));
+        assert_eq!(parse("1.0-DEV").dev, Some(0));
+        assert_eq!(parse("1.0a.post.dev").to_string(), "1.0a0.post0.dev0");
+    }
+
+    #[test]
+    fn test_invalid_spellings_are_errors() {
+        for version in ["1.0-", "1.0+", "1.0-1-1", "1.0.dev.post", "a1.0", "1..0", "1.0foo", ""] {
+            assert!(matches!(rip::parse_version(version.to_string()),
+                             Err(rip::Error::InvalidVersion { .. })),
+                    "{} should not parse",
+                    version);
+        }
+    }
+
+    #[test]
+    fn test_overflowing_suffix_numbers_are_errors() {
+        for version in ["99999999999999999999!1.0",
+                        "1.0a99999999999999999999",
+                        "1.0.post99999999999999999999",
+                        "1.0-99999999999999999999",
+                        "1.0.dev99999999999999999999"]
+        {
+            assert!(matches!(rip::parse_version(version.to_string()),
+                             Err(rip::Error::NumericOverflow { .. })),
+                    "{} should overflow",
+                    version);
+        }
+    }
+}
+
+mod version_inspect {
+    fn parse(version: &str) -> rip::Version {
+        rip::parse_version(version.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_release_kinds() {
+        assert!(parse("1.0a1").is_prerelease());
+        assert!(parse("1.0.dev0").is_prerelease());
+        assert!(parse("1.0.post1.dev0").is_prerelease());
+        assert!(!parse("1.0.post1").is_prerelease());
+        assert!(!parse("1.0+local").is_prerelease());
+
+        assert!(parse("1.0.post1.dev0").is_devrelease());
+        assert!(!parse("1.0a1").is_devrelease());
+
+        assert!(parse("1.0-1").is_postrelease());
+        assert!(!parse("1.0rc1").is_postrelease());
+    }
+
+    #[test]
+    fn test_major_minor_micro() {
+        let version = parse("2!3.4.5.6");
+        assert_eq!((version.major(), version.minor(), version.micro()), (3, 4, 5));
+        let version = parse("7");
+        assert_eq!((version.major(), version.minor(), version.micro()), (7, 0, 0));
+    }
+
+    #[test]
+    fn test_base_version_and_public() {
+        let version = parse("1!2.0rc1.post2.dev3+Local-7");
+        assert_eq!(version.base_version().to_string(), "1!2.0");
+        assert_eq!(version.base_version().version_string, "1!2.0");
+        assert_eq!(version.public().to_string(), "1!2.0rc1.post2.dev3");
+        assert_eq!(parse("1.0").public(), parse("1.0"));
+    }
+}
+
+mod version_wide_numbers {
+    fn parse(version: &str) -> rip::Version {
+        rip::parse_version(version.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_timestamp_versions() {
+        let version = parse("20231015123045");
+        assert_eq!(version.release.as_slice(), [20231015123045]);
+        assert!(parse("20231015123045") < parse("20231015123046"));
+        assert!(parse("1.20231015123045") > parse("1.4294967296"));
+        assert_eq!(parse("2023.10.15.123045").to_string(), "2023.10.15.123045");
+    }
+
+    #[test]
+    fn test_wide_epoch_pre_post_dev_and_local() {
+        let version = parse("20231015!1.0rc20231015123045.post20231015123045.dev20231015123045+build.20231015123045");
+        assert_eq!(version.epoch, Some(20231015));
+        assert_eq!(version.post, Some(20231015123045));
+        assert_eq!(version.dev, Some(20231015123045));
+        assert_eq!(version.to_string(),
+                   "20231015!1.0rc20231015123045.post20231015123045.dev20231015123045+build.20231015123045");
+        assert_eq!(parse(&u64::MAX.to_string()).major(), u64::MAX);
+    }
+}
+
+mod version_from_str {
+    use rip::Version;
+
+    #[test]
+    fn test_str_parse() {
+        let version: Version = "1.0-ALPHA.1".parse().unwrap();
+        assert_eq!(version, rip::parse_version("1.0a1".to_string()).unwrap());
+        assert!("1.x".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        assert_eq!(Version::try_from("2!1.0").unwrap().to_string(), "2!1.0");
+        assert_eq!(Version::try_from("1.x"),
+                   Err(rip::Error::InvalidVersion { input: "1.x".to_string(),
+                                                    span: 0..3 }));
+    }
+}
+
 mod version_compare {
 
     #[test]
     fn test_version_eq() {
         let version1 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: None,
                                       dev: None,
                                       local: None,
                                       version_string: "1.5.3".to_string() };
         let version2 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: None,
                                       dev: None,
@@ -163,14 +486,14 @@ mod version_compare {
     #[test]
     fn test_version_compare_basic_release() {
         let version1 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: None,
                                       dev: None,
                                       local: None,
                                       version_string: "1.5.3".to_string() };
         let version2 = rip::Version { epoch: None,
-                                      release: [1, 5, 4].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 4]),
                                       pre: None,
                                       post: None,
                                       dev: None,
@@ -182,14 +505,14 @@ mod version_compare {
     #[test]
     fn test_version_compare_epoch() {
         let version1 = rip::Version { epoch: None,
-                                      release: [2022, 2, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[2022, 2, 3]),
                                       pre: None,
                                       post: None,
                                       dev: None,
                                       local: None,
                                       version_string: "1.5.3".to_string() };
         let version2 = rip::Version { epoch: Some(1),
-                                      release: [1, 5, 4].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 4]),
                                       pre: None,
                                       post: None,
                                       dev: None,
@@ -201,15 +524,15 @@ mod version_compare {
     #[test]
     fn test_version_compare_pre_release() {
         let version1 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
-                                      pre: Some((rip::PreReleaseType::Alpha, [1].to_vec())),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
+                                      pre: Some((rip::PreReleaseType::Alpha, 1)),
                                       post: None,
                                       dev: None,
                                       local: None,
                                       version_string: "".to_string() };
         let version2 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
-                                      pre: Some((rip::PreReleaseType::Beta, [1].to_vec())),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
+                                      pre: Some((rip::PreReleaseType::Beta, 1)),
                                       post: None,
                                       dev: None,
                                       local: None,
@@ -220,15 +543,15 @@ mod version_compare {
     #[test]
     fn test_version_compare_pre_release_different_release() {
         let version1 = rip::Version { epoch: None,
-                                      release: [1, 5, 4].to_vec(),
-                                      pre: Some((rip::PreReleaseType::Alpha, [1].to_vec())),
+                                      release: rip::Release::from_slice(&[1, 5, 4]),
+                                      pre: Some((rip::PreReleaseType::Alpha, 1)),
                                       post: None,
                                       dev: None,
                                       local: None,
                                       version_string: "".to_string() };
         let version2 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
-                                      pre: Some((rip::PreReleaseType::Beta, [1].to_vec())),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
+                                      pre: Some((rip::PreReleaseType::Beta, 1)),
                                       post: None,
                                       dev: None,
                                       local: None,
@@ -239,14 +562,14 @@ mod version_compare {
     #[test]
     fn test_version_compare_post_release() {
         let version1 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: Some(1),
                                       dev: None,
                                       local: None,
                                       version_string: "".to_string() };
         let version2 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: Some(2),
                                       dev: None,
@@ -258,14 +581,14 @@ mod version_compare {
     #[test]
     fn test_version_compare_dev_release() {
         let version1 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: None,
                                       dev: Some(1),
                                       local: None,
                                       version_string: "".to_string() };
         let version2 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: None,
                                       dev: Some(2),
@@ -277,19 +600,271 @@ mod version_compare {
     #[test]
     fn test_version_compare_local_release() {
         let version1 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: None,
                                       dev: None,
-                                      local: Some("local1".to_string()),
+                                      local: Some(rip::parse_local_version("local1".to_string()).unwrap()),
                                       version_string: "".to_string() };
         let version2 = rip::Version { epoch: None,
-                                      release: [1, 5, 3].to_vec(),
+                                      release: rip::Release::from_slice(&[1, 5, 3]),
                                       pre: None,
                                       post: None,
                                       dev: None,
-                                      local: Some("local2".to_string()),
+                                      local: Some(rip::parse_local_version("local2".to_string()).unwrap()),
                                       version_string: "".to_string() };
         assert!(version1 < version2);
     }
+
+    fn parse(version: &str) -> rip::Version {
+        rip::parse_version(version.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_version_eq_trailing_zeros_and_epoch() {
+        assert_eq!(parse("1.0"), parse("1.0.0"));
+        assert_eq!(parse("1"), parse("0!1.0.0.0"));
+        assert_eq!(parse("1.0+abc"), parse("1.0.0+ABC"));
+        assert_ne!(parse("1.0"), parse("1.0+abc"));
+        assert_eq!(parse("1.0").cmp(&parse("1.0.0")), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_version_hash_consistent_with_eq() {
+        let versions = std::collections::HashSet::from([parse("1.0"), parse("1.0.0"), parse("0!1"), parse("1.0.post0")]);
+        assert_eq!(versions.len(), 2);
+    }
+
+    #[test]
+    fn test_version_total_order() {
+        let expected = ["1.0.dev456",
+                        "1.0a1",
+                        "1.0a2.dev456",
+                        "1.0a12.dev456",
+                        "1.0a12",
+                        "1.0b1.dev456",
+                        "1.0b2",
+                        "1.0b2.post345.dev456",
+                        "1.0b2.post345",
+                        "1.0rc1.dev456",
+                        "1.0rc1",
+                        "1.0",
+                        "1.0+abc.5",
+                        "1.0+abc.7",
+                        "1.0+5",
+                        "1.0.post456.dev34",
+                        "1.0.post456",
+                        "1.1.dev1",
+                        "1!0.1"];
+        let mut versions = expected.iter()
+                                   .rev()
+                                   .map(|version| parse(version))
+                                   .collect::<Vec<rip::Version>>();
+        versions.sort();
+        let sorted = versions.iter().map(|version| version.to_string()).collect::<Vec<String>>();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn test_version_btree_map_key() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(parse("2.0"), "two");
+        map.insert(parse("2.0.0"), "two again");
+        map.insert(parse("1.0rc1"), "release candidate");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&parse("2")), Some(&"two again"));
+        assert_eq!(map.keys().next(), Some(&parse("1.0rc1")));
+    }
+}
+
+mod local_version {
+    use either::Either;
+
+    fn parse(version: &str) -> rip::Version {
+        rip::parse_version(version.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_local_segments() {
+        let local = parse("1.0+Ubuntu-1_cu118.007").local.unwrap();
+        assert_eq!(local.parsed,
+                   vec![Either::Left("ubuntu".to_string()),
+                        Either::Right(1),
+                        Either::Left("cu118".to_string()),
+                        Either::Right(7)]);
+        assert_eq!(local.local_version_string, "Ubuntu-1_cu118.007");
+        assert_eq!(local.to_string(), "ubuntu.1.cu118.7");
+    }
+
+    #[test]
+    fn test_local_numeric_segments_compare_as_numbers() {
+        assert!(parse("1.0+abc.10") > parse("1.0+abc.9"));
+        assert!(parse("1.0+cu118") > parse("1.0+cu111"));
+        assert_eq!(parse("1.0+abc.09"), parse("1.0+ABC-9"));
+    }
+
+    #[test]
+    fn test_local_numeric_segments_sort_after_alphanumeric() {
+        assert!(parse("1.0+1") > parse("1.0+abc"));
+        assert!(parse("1.0+abc.1") > parse("1.0+abc.xyz"));
+    }
+
+    #[test]
+    fn test_local_longer_label_sorts_after_prefix() {
+        assert!(parse("1.0+abc.1") > parse("1.0+abc"));
+        assert!(parse("1.0+abc") > parse("1.0"));
+    }
+
+    #[test]
+    fn test_parse_local_version_errors() {
+        assert!(rip::parse_local_version("abc..1".to_string()).is_err());
+        assert_eq!(rip::parse_version("1.0+abc.99999999999999999999".to_string()),
+                   Err(rip::Error::NumericOverflow { input: "1.0+abc.99999999999999999999".to_string(),
+                                                     span: 8..28 }));
+    }
+}
+
+mod legacy_version {
+    use rip::AnyVersion;
+
+    fn legacy(version: &str) -> rip::LegacyVersion {
+        rip::parse_legacy_version(version.to_string())
+    }
+
+    #[test]
+    fn test_parse_any_version_falls_back_to_legacy() {
+        assert!(rip::parse_any_version("2004d".to_string()).is_legacy());
+        assert!(rip::parse_any_version("1.0-SNAPSHOT".to_string()).is_legacy());
+        assert_eq!(rip::parse_any_version("1.0.0".to_string()).as_pep440(),
+                   Some(&rip::parse_version("1.0".to_string()).unwrap()));
+    }
+
+    #[test]
+    fn test_legacy_display_keeps_original_string() {
+        assert_eq!(legacy("1.0-SNAPSHOT").to_string(), "1.0-SNAPSHOT");
+        assert_eq!(rip::parse_any_version("2004d".to_string()).to_string(), "2004d");
+    }
+
+    #[test]
+    fn test_legacy_ordering() {
+        assert!(legacy("2004d") < legacy("2004e"));
+        assert!(legacy("2004d") < legacy("2005"));
+        assert!(legacy("1.0-SNAPSHOT") > legacy("1.0"));
+        assert!(legacy("1.0.x") > legacy("1.0"));
+        assert!(legacy("1.0pre1") < legacy("1.0"));
+        assert_eq!(legacy("1.0.0-FOO"), legacy("1-foo"));
+    }
+
+    #[test]
+    fn test_legacy_sorts_before_pep440() {
+        let mut versions =
+            ["0.1", "2004d", "1.0-SNAPSHOT", "0!0.0.dev0"].iter()
+                                                          .map(|version| rip::parse_any_version(version.to_string()))
+                                                          .collect::<Vec<AnyVersion>>();
+        versions.sort();
+        let sorted = versions.iter().map(|version| version.to_string()).collect::<Vec<String>>();
+        assert_eq!(sorted, ["1.0-SNAPSHOT", "2004d", "0.0.dev0", "0.1"]);
+    }
+}
+
+mod parse_errors {
+    use rip::Error;
+
+    #[test]
+    fn test_invalid_version() {
+        assert_eq!(rip::parse_version("not-a-version".to_string()),
+                   Err(Error::InvalidVersion { input: "not-a-version".to_string(),
+                                               span: 0..13 }));
+    }
+
+    #[test]
+    fn test_numeric_overflow_span() {
+        assert_eq!(rip::parse_version("1.99999999999999999999.0".to_string()),
+                   Err(Error::NumericOverflow { input: "1.99999999999999999999.0".to_string(),
+                                                span: 2..22 }));
+    }
+
+    #[test]
+    fn test_error_message() {
+        let error = rip::parse_version("1.0foo".to_string()).unwrap_err();
+        assert_eq!(error.to_string(), "1.0foo is not a valid version");
+        let boxed: Box<dyn std::error::Error> = Box::new(error);
+        assert!(boxed.source().is_none());
+    }
+
+    #[test]
+    fn test_missing_metadata_field() {
+        assert_eq!(rip::parse_metadata("Metadata-Version: 2.1\nName: pandas".to_string()).unwrap_err(),
+                   Error::MissingMetadataField { field: "Version".to_string() });
+    }
+
+    #[test]
+    fn test_malformed_header() {
+        let metadata = "Metadata-Version: 2.1\nName:\nVersion: 1.0".to_string();
+        assert_eq!(rip::parse_metadata(metadata.clone()).unwrap_err(),
+                   Error::MalformedHeader { input: metadata,
+                                            span: 22..27 });
+    }
+
+    #[test]
+    fn test_invalid_version_in_metadata_span() {
+        let metadata = "Name: pandas\nMetadata-Version: 2.x\nVersion: 1.0".to_string();
+        assert_eq!(rip::parse_metadata(metadata.clone()).unwrap_err(),
+                   Error::InvalidVersion { input: metadata,
+                                           span: 31..34 });
+    }
+
+    #[test]
+    fn test_unsupported_metadata_version() {
+        let metadata = "Metadata-Version: 3.0\nName: pandas\nVersion: 1.5.3".to_string();
+        assert_eq!(rip::parse_metadata(metadata.clone()).unwrap_err(),
+                   Error::UnsupportedMetadataVersion { input: metadata,
+                                                       span: 18..21 });
+    }
+}
+
+mod version_display {
+    fn canonical(version: &str) -> String {
+        rip::parse_version(version.to_string()).unwrap().to_canonical_string()
+    }
+
+    #[test]
+    fn test_canonical_pre_release() {
+        assert_eq!(canonical("1.0-ALPHA.1"), "1.0a1");
+        assert_eq!(canonical("1.0.beta2"), "1.0b2");
+        assert_eq!(canonical("1.0c3"), "1.0rc3");
+        assert_eq!(canonical("1.0-preview_4"), "1.0rc4");
+    }
+
+    #[test]
+    fn test_canonical_post_release() {
+        assert_eq!(canonical("v1.0-r3"), "1.0.post3");
+        assert_eq!(canonical("1.0-1"), "1.0.post1");
+        assert_eq!(canonical("1.0_rev7"), "1.0.post7");
+    }
+
+    #[test]
+    fn test_canonical_epoch_dev_and_local() {
+        assert_eq!(canonical("0!1.0"), "1.0");
+        assert_eq!(canonical("2!1.0-DEV3"), "2!1.0.dev3");
+        assert_eq!(canonical(" 1.0+Ubuntu-1_x "), "1.0+ubuntu.1.x");
+        assert_eq!(canonical("01.002.0"), "1.2.0");
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for version in ["1.0-ALPHA.1",
+                        "v1.0-r3",
+                        "1.0-1",
+                        "3!2.0b4.post5.dev6+abc-7",
+                        "1.2.3.4.5rc0",
+                        "1.0+CUDA_11"]
+        {
+            let parsed = rip::parse_version(version.to_string()).unwrap();
+            let canonical = parsed.to_string();
+            let reparsed = rip::parse_version(canonical.clone()).unwrap();
+            assert_eq!(reparsed, parsed);
+            assert_eq!(reparsed.to_string(), canonical);
+        }
+    }
 }
@@ -0,0 +1,55 @@
+mod common;
+
+mod parser_equivalence {
+    use super::common::{key_cmp, regex_parse_version, version_corpus};
+
+    #[test]
+    fn test_hand_written_parser_matches_regex() {
+        for version in version_corpus(20000) {
+            assert_eq!(format!("{:?}", rip::parse_version(version.clone())),
+                       format!("{:?}", regex_parse_version(version.clone())),
+                       "{:?} parses differently",
+                       version);
+        }
+    }
+
+    #[test]
+    fn test_hand_written_local_parser_matches_regex() {
+        for local in ["ubuntu-1",
+                      "abc.10",
+                      "Cu118_7",
+                      "",
+                      "a..b",
+                      "a-",
+                      "-a",
+                      "a b",
+                      "1.99999999999999999999"]
+        {
+            let version = format!("1.0+{}", local);
+            let expected = regex_parse_version(version).map(|version| version.local.unwrap());
+            let parsed = rip::parse_local_version(local.to_string());
+            assert_eq!(format!("{:?}", parsed.as_ref().map_err(|_| ())),
+                       format!("{:?}", expected.as_ref().map_err(|_| ())),
+                       "{:?} parses differently",
+                       local);
+        }
+    }
+
+    #[test]
+    fn test_packed_sort_matches_full_key() {
+        let versions = version_corpus(3000).into_iter()
+                                           .filter_map(|version| rip::parse_version(version).ok())
+                                           .collect::<Vec<rip::Version>>();
+        let common = versions.iter()
+                             .filter(|version| version.epoch.unwrap_or(0) == 0 && version.local.is_none())
+                             .cloned()
+                             .collect::<Vec<rip::Version>>();
+        for mut versions in [versions, common] {
+            let mut expected = versions.clone();
+            expected.sort_by(key_cmp);
+            rip::sort_versions(&mut versions);
+            assert_eq!(versions.iter().map(|version| version.to_string()).collect::<Vec<String>>(),
+                       expected.iter().map(|version| version.to_string()).collect::<Vec<String>>());
+        }
+    }
+}
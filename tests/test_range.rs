@@ -0,0 +1,176 @@
+mod version_range {
+    use rip::range::VersionRange;
+    use rip::specifier::SpecifierSet;
+    use rip::Version;
+
+    const VERSIONS: [&str; 33] = ["0.dev0",
+                                  "0",
+                                  "0.9",
+                                  "1.0.dev0",
+                                  "1.0a1",
+                                  "1.0rc1.dev1",
+                                  "1.0rc1",
+                                  "1.0rc1.post1",
+                                  "1.0",
+                                  "1.0+local",
+                                  "1.0.post0.dev0",
+                                  "1.0.post1",
+                                  "1.0.post2",
+                                  "1.0.1",
+                                  "1.0.0.1",
+                                  "1.4",
+                                  "1.4.5",
+                                  "1.4.99",
+                                  "1.5.dev0",
+                                  "1.5a1",
+                                  "1.5",
+                                  "1.5+local",
+                                  "1.5.1",
+                                  "1.9.post3",
+                                  "2.0.dev1",
+                                  "2.0rc1",
+                                  "2.0",
+                                  "2.0.post1.dev0",
+                                  "2.0.post1",
+                                  "2.1",
+                                  "10",
+                                  "1!0.5",
+                                  "1!2.0"];
+
+    const SPECIFIERS: [&str; 23] = ["",
+                                    ">=1.0",
+                                    ">1.0",
+                                    ">1.0.dev0",
+                                    ">1.0rc1",
+                                    ">1.0.post1",
+                                    "<2.0",
+                                    "<2.0rc1",
+                                    "<2.0.post1",
+                                    "<=1.5",
+                                    "==1.0",
+                                    "!=1.5",
+                                    "==1.4.*",
+                                    "!=1.*",
+                                    "~=1.4",
+                                    "~=1.4.5",
+                                    "~=1.0rc1",
+                                    ">=1.0,<2.0,!=1.5",
+                                    ">=1.0,!=1.4.*,<2",
+                                    ">=2,<1.5",
+                                    "==1!2.*",
+                                    ">=0.dev0",
+                                    "<0.dev0"];
+
+    fn range(specifiers: &str) -> VersionRange {
+        VersionRange::from_specifier_set(&specifiers.parse().unwrap()).unwrap()
+    }
+
+    fn specifier_set_contains(specifiers: &SpecifierSet, version: &Version) -> bool {
+        specifiers.specifiers.iter().all(|specifier| specifier.contains(version))
+    }
+
+    #[test]
+    fn test_range_contains_what_specifiers_contain() {
+        for specifiers in SPECIFIERS {
+            let range = range(specifiers);
+            let specifiers: SpecifierSet = specifiers.parse().unwrap();
+            for version in VERSIONS {
+                let version: Version = version.parse().unwrap();
+                assert_eq!(range.contains(&version),
+                           specifier_set_contains(&specifiers, &version),
+                           "{} ({}) and {}",
+                           specifiers,
+                           range,
+                           version);
+            }
+        }
+    }
+
+    #[test]
+    fn test_emptiness() {
+        assert!(range(">=2,<1.5").is_empty());
+        assert!(range("==1.0,!=1.0").is_empty());
+        assert!(range("==1.4.*,>=1.5").is_empty());
+        assert!(range("<0.dev0").is_empty());
+        assert!(!range(">=2,<2.0.post0").is_empty());
+        assert!(range("<1.0,>1.0").intersection(&range(">0.9.post0")).is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        for this in SPECIFIERS {
+            for other in SPECIFIERS {
+                let (this, other) = (range(this), range(other));
+                let (intersection, union) = (this.intersection(&other), this.union(&other));
+                for version in VERSIONS {
+                    let version: Version = version.parse().unwrap();
+                    let (in_this, in_other) = (this.contains(&version), other.contains(&version));
+                    assert_eq!(intersection.contains(&version), in_this && in_other);
+                    assert_eq!(union.contains(&version), in_this || in_other);
+                    assert_eq!(this.complement().contains(&version), !in_this);
+                }
+                assert_eq!(this.complement().complement(), this);
+                assert_eq!(this.union(&this.complement()), VersionRange::full());
+            }
+        }
+    }
+
+    #[test]
+    fn test_equal_sets_have_equal_ranges() {
+        assert_eq!(range(">=1.0,<=1.0"), range("==1.0"));
+        assert_eq!(range("==1.0.0"), range("==1"));
+        assert_eq!(range(">=1.4,<1.5"), range("==1.4.*").intersection(&range(">=1.4")));
+        assert_eq!(range("<1.0rc1").union(&range(">=1.0rc1")), VersionRange::full());
+        assert_eq!(range("<1.0").union(&range(">=1.0")),
+                   range(">=1.0.dev0,<=1.0,!=1.0").complement());
+        assert!(!range("<1.0").union(&range(">=1.0")).contains(&"1.0rc1".parse().unwrap()));
+        assert_eq!(range("!=1.5").complement(), range("==1.5"));
+        assert_eq!(range(">=0.dev0"), VersionRange::full());
+    }
+
+    #[test]
+    fn test_minimal_specifiers() {
+        for (specifiers, expected) in [("", ""),
+                                       (">=1.0", ">=1.0"),
+                                       (">1.0", ">1.0"),
+                                       ("<2.0", "<2.0"),
+                                       ("<=2.0", "<=2.0"),
+                                       ("<1.0.post0.dev0", "<=1.0"),
+                                       ("<=1.5,>=1.5", "==1.5"),
+                                       ("<1.5,>=1.4.dev0", "==1.4.*"),
+                                       (">=1.4,<2", "~=1.4"),
+                                       (">=1.4.0,<1.5", "~=1.4.0"),
+                                       (">=1.0,<2.0,!=1.5", "~=1.0,!=1.5"),
+                                       (">=1.0,<1.8,!=1.5", ">=1.0,<1.8,!=1.5"),
+                                       (">=1.0,!=1.4.*,!=1.5,<2", "~=1.0,!=1.4.*,!=1.5"),
+                                       (">=2,<1.5", "<0.dev0"),
+                                       (">1.0rc1,<1.0.post1", ">1.0rc1,<1.0.post1"),
+                                       (">1.0.dev0", ">1.0.dev0"),
+                                       (">1.0rc1.dev2", ">1.0rc1.dev2"),
+                                       (">1.0.dev0,<2,!=1.5", ">1.0.dev0,<2,!=1.5")]
+        {
+            let range = range(specifiers);
+            let rendered = range.to_specifier_set().unwrap();
+            assert_eq!(rendered.to_string(), expected, "{}", specifiers);
+            assert_eq!(VersionRange::from_specifier_set(&rendered).unwrap(), range);
+        }
+        assert_eq!(range(">=1.0").complement().to_specifier_set().unwrap().to_string(),
+                   "<=1.0,!=1.0");
+    }
+
+    #[test]
+    fn test_unrepresentable() {
+        assert!(range("<1").union(&range(">2")).to_specifier_set().is_none());
+        assert!(range(">1.0").complement().to_specifier_set().is_none());
+        assert!(range(">1.0.dev0").complement().to_specifier_set().is_none());
+        assert!(VersionRange::from_specifier_set(&"===1.0".parse().unwrap()).is_err());
+        assert!(VersionRange::from_specifier_set(&"==1.0+local".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(range(">=1.0,!=1.5").to_string(), "[1.0, 1.5) ∪ [1.5.post0.dev0, ∞)");
+        assert_eq!(range("<1.0").to_string(), "(-∞, 1.0.dev0)");
+        assert_eq!(VersionRange::empty().to_string(), "∅");
+    }
+}
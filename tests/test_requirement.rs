@@ -0,0 +1,424 @@
+mod requirement_parse {
+    use rip::marker::MarkerTree;
+    use rip::name::ExtraName;
+    use rip::requirement::{parse_requirement, Requirement};
+    use rip::specifier::SpecifierSet;
+
+    #[test]
+    fn test_parse_name_extras_and_specifiers() {
+        let requirement = parse_requirement("requests[security, socks] >=2.8.1, <3".to_string()).unwrap();
+        assert_eq!(requirement.name.as_str(), "requests");
+        assert_eq!(requirement.extras.iter().map(ExtraName::as_str).collect::<Vec<&str>>(),
+                   vec!["security", "socks"]);
+        assert_eq!(requirement.specifiers, ">=2.8.1,<3".parse::<SpecifierSet>().unwrap());
+        assert_eq!(requirement.url, None);
+        assert_eq!(requirement.marker, None);
+
+        let parenthesized = parse_requirement("requests [security,socks] (>=2.8.1,<3)".to_string()).unwrap();
+        assert_eq!(parenthesized, requirement);
+
+        let bare = parse_requirement("zope.interface".to_string()).unwrap();
+        assert_eq!(bare.name.as_str(), "zope.interface");
+        assert!(bare.extras.is_empty());
+        assert_eq!(bare.specifiers, SpecifierSet::default());
+    }
+
+    #[test]
+    fn test_parse_url_and_marker() {
+        let requirement =
+            parse_requirement("pip @ https://github.com/pypa/pip/archive/22.0.zip ; python_version >= '3.7'".to_string()).unwrap();
+        assert_eq!(requirement.name.as_str(), "pip");
+        assert_eq!(requirement.url.as_deref(),
+                   Some("https://github.com/pypa/pip/archive/22.0.zip"));
+        assert_eq!(requirement.marker,
+                   Some("python_version >= \"3.7\"".parse::<MarkerTree>().unwrap()));
+
+        // Without whitespace the `;` belongs to the URL
+        let requirement = parse_requirement("name @ https://example.com/name.zip;python_version".to_string()).unwrap();
+        assert_eq!(requirement.url.as_deref(),
+                   Some("https://example.com/name.zip;python_version"));
+        assert_eq!(requirement.marker, None);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for (requirement, expected) in [("requests [ security ] ( >= 2.8.1 )", "requests[security]>=2.8.1"),
+                                        ("name>=1.0,<2;python_version<'3.8'", "name>=1.0,<2; python_version < \"3.8\""),
+                                        ("name @ file:///tmp/name.whl ; extra == 'test'",
+                                         "name @ file:///tmp/name.whl ; extra == \"test\""),
+                                        ("name; os.name == 'nt'", "name; os_name == \"nt\"")]
+        {
+            let requirement: Requirement = requirement.parse().unwrap();
+            assert_eq!(requirement.to_string(), expected);
+            assert_eq!(requirement.to_string().parse::<Requirement>().unwrap(), requirement);
+        }
+    }
+
+    #[test]
+    fn test_display_simplified_marker() {
+        let mut requirement: Requirement = "name; os_name == 'nt' and os_name == 'posix'".parse().unwrap();
+        requirement.marker = requirement.marker.map(|marker| marker.simplify());
+        assert_eq!(requirement.to_string(), "name; \"\" != \"\"");
+        let reparsed: Requirement = requirement.to_string().parse().unwrap();
+        assert!(reparsed.marker.unwrap().is_false());
+
+        requirement.marker = Some(MarkerTree::TRUE);
+        assert_eq!(requirement.to_string(), "name");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_requirement("-name".to_string()),
+                   Err(rip::Error::InvalidRequirement { input: "-name".to_string(),
+                                                        span: 0..5,
+                                                        reason:
+                                                            "a package name must start and end with a letter or digit".to_string() }));
+        assert!(matches!(parse_requirement("name[extra".to_string()),
+                         Err(rip::Error::InvalidRequirement { span, .. }) if span == (4..10)));
+        assert!(matches!(parse_requirement("name (>=1.0".to_string()),
+                         Err(rip::Error::InvalidRequirement { span, .. }) if span == (5..11)));
+        assert!(matches!(parse_requirement("name 1.0".to_string()),
+                         Err(rip::Error::InvalidRequirement { span, .. }) if span == (5..8)));
+        assert!(matches!(parse_requirement("name >=1.x".to_string()),
+                         Err(rip::Error::InvalidVersion { span, .. }) if span == (7..10)));
+        assert!(matches!(parse_requirement("name @ ".to_string()),
+                         Err(rip::Error::InvalidRequirement { .. })));
+        assert!(matches!(parse_requirement("name ; python_version >= '3.8' xor".to_string()),
+                         Err(rip::Error::InvalidMarker { span, .. }) if span == (31..34)));
+    }
+}
+
+mod marker_parse {
+    use rip::marker::{parse_marker, MarkerExpression, MarkerOperator, MarkerTree, MarkerValue, MarkerVariable};
+    use rip::specifier::Operator;
+
+    #[test]
+    fn test_parse_expression() {
+        assert_eq!(parse_marker("python_version >= '3.8'".to_string()).unwrap(),
+                   MarkerTree::Expression(MarkerExpression { left: MarkerValue::Variable(MarkerVariable::PythonVersion),
+                                                             operator: MarkerOperator::Compare(Operator::GreaterThanEqual),
+                                                             right: MarkerValue::String("3.8".to_string()) }));
+        assert_eq!(parse_marker("'linux' not  in sys_platform".to_string()).unwrap(),
+                   MarkerTree::Expression(MarkerExpression { left: MarkerValue::String("linux".to_string()),
+                                                             operator: MarkerOperator::NotIn,
+                                                             right: MarkerValue::Variable(MarkerVariable::SysPlatform) }));
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let marker = parse_marker("os_name == 'a' or os_name == 'b' and (os_name == 'c' or os_name == 'd')".to_string()).unwrap();
+        match marker {
+            MarkerTree::Or(children) => {
+                assert_eq!(children.len(), 2);
+                assert!(matches!(&children[1], MarkerTree::And(grandchildren) if grandchildren.len() == 2));
+            }
+            _ => panic!("expected or at the top"),
+        }
+
+        // Parentheses of the same kind are flattened
+        let marker = parse_marker("(os_name == 'a' and os_name == 'b') and os_name == 'c'".to_string()).unwrap();
+        assert!(matches!(marker, MarkerTree::And(children) if children.len() == 3));
+    }
+
+    #[test]
+    fn test_display() {
+        for (marker, expected) in [("python_version>='3.8'and(sys_platform=='linux'or extra=='test')",
+                                    "python_version >= \"3.8\" and (sys_platform == \"linux\" or extra == \"test\")"),
+                                   ("(os_name == 'nt') or (os_name == 'posix')", "os_name == \"nt\" or os_name == \"posix\""),
+                                   ("platform_version in '\"quoted\"'", "platform_version in '\"quoted\"'")]
+        {
+            let marker: MarkerTree = marker.parse().unwrap();
+            assert_eq!(marker.to_string(), expected);
+            assert_eq!(marker.to_string().parse::<MarkerTree>().unwrap(), marker);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_marker("python_versions == '3.8'".to_string()),
+                   Err(rip::Error::InvalidMarker { input: "python_versions == '3.8'".to_string(),
+                                                   span: 0..15,
+                                                   reason: "expected a marker variable like python_version or a quoted string".to_string() }));
+        assert!(matches!(parse_marker("os_name = 'nt'".to_string()),
+                         Err(rip::Error::InvalidMarker { span, .. }) if span == (8..9)));
+        assert!(matches!(parse_marker("os_name == 'nt".to_string()),
+                         Err(rip::Error::InvalidMarker { span, .. }) if span == (11..14)));
+        assert!(matches!(parse_marker("(os_name == 'nt'".to_string()),
+                         Err(rip::Error::InvalidMarker { span, .. }) if span == (0..16)));
+        assert!(parse_marker("".to_string()).is_err());
+    }
+}
+
+mod metadata_requires_dist {
+    use rip::parse_metadata;
+
+    #[test]
+    fn test_requires_dist_lines() {
+        let metadata = parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nRequires-Dist: numpy >=1.21\n\
+                                       Requires-Dist: pytest ; extra == 'test'\n"
+                                                                                 .to_string()).unwrap();
+        let requires_dist = metadata.requires_dist.unwrap();
+        assert_eq!(requires_dist.len(), 2);
+        assert_eq!(requires_dist[0].name.as_str(), "numpy");
+        assert_eq!(requires_dist[1].to_string(), "pytest; extra == \"test\"");
+
+        let metadata = parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\n".to_string()).unwrap();
+        assert_eq!(metadata.requires_dist, None);
+    }
+
+    #[test]
+    fn test_requires_dist_error_spans() {
+        let document = "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nRequires-Dist: numpy >=1.x\n";
+        let offset = document.find("numpy").unwrap();
+        let metadata = parse_metadata(document.to_string()).unwrap();
+        assert_eq!(metadata.requires_dist, None);
+        assert!(matches!(metadata.invalid_fields.as_slice(),
+                         [rip::Error::InvalidVersion { span, .. }] if *span == (offset + 8..offset + 11)));
+    }
+}
+
+mod marker_evaluate {
+    use rip::marker::{MarkerEnvironment, MarkerTree};
+    use rip::requirement::Requirement;
+
+    fn linux_cpython_311() -> MarkerEnvironment {
+        MarkerEnvironment { implementation_name: "cpython".to_string(),
+                            implementation_version: "3.11.4".to_string(),
+                            os_name: "posix".to_string(),
+                            platform_machine: "x86_64".to_string(),
+                            platform_python_implementation: "CPython".to_string(),
+                            platform_release: "6.1.0-10-amd64".to_string(),
+                            platform_system: "Linux".to_string(),
+                            platform_version: "#1 SMP PREEMPT_DYNAMIC Debian 6.1.38-1".to_string(),
+                            python_full_version: "3.11.4".to_string(),
+                            python_version: "3.11".to_string(),
+                            sys_platform: "linux".to_string() }
+    }
+
+    fn evaluate(marker: &str, extras: &[&str]) -> bool {
+        marker.parse::<MarkerTree>().unwrap().evaluate(&linux_cpython_311(), extras)
+    }
+
+    #[test]
+    fn test_version_comparisons() {
+        // As strings "3.11" < "3.8", as versions it is the other way around
+        assert!(evaluate("python_version >= '3.8'", &[]));
+        assert!(!evaluate("python_version < '3.8'", &[]));
+        assert!(evaluate("python_full_version == '3.11.*'", &[]));
+        assert!(evaluate("python_version ~= '3.9'", &[]));
+        assert!(evaluate("'3.12' > python_version", &[]));
+        assert!(evaluate("python_full_version != '3.11'", &[]));
+        assert!(!evaluate("python_full_version != '3.11.4'", &[]));
+        assert!(evaluate("implementation_version > '3.11.4rc1'", &[]));
+    }
+
+    #[test]
+    fn test_string_comparisons() {
+        assert!(evaluate("sys_platform == 'linux'", &[]));
+        assert!(!evaluate("sys_platform == 'Linux'", &[]));
+        assert!(evaluate("platform_system != 'Windows'", &[]));
+        assert!(evaluate("platform_release >= '6'", &[]));
+        assert!(!evaluate("os_name ~= 'posix'", &[]));
+        assert!(evaluate("os_name === 'posix'", &[]));
+    }
+
+    #[test]
+    fn test_in_and_not_in() {
+        assert!(evaluate("'linux' in sys_platform", &[]));
+        assert!(evaluate("platform_machine in 'x86_64 aarch64'", &[]));
+        assert!(evaluate("'Debian' in platform_version", &[]));
+        assert!(evaluate("'win' not in sys_platform", &[]));
+        assert!(!evaluate("platform_python_implementation not in 'CPython PyPy'", &[]));
+    }
+
+    #[test]
+    fn test_and_or() {
+        assert!(evaluate("python_version >= '3.8' and sys_platform == 'linux'", &[]));
+        assert!(!evaluate("python_version >= '3.8' and sys_platform == 'win32'", &[]));
+        assert!(evaluate("sys_platform == 'win32' or (os_name == 'posix' and platform_machine == 'x86_64')",
+                         &[]));
+    }
+
+    #[test]
+    fn test_extra() {
+        assert!(!evaluate("extra == 'test'", &[]));
+        assert!(evaluate("extra == 'test'", &["test"]));
+        assert!(evaluate("extra == 'Dev_Tools'", &["dev-tools"]));
+        assert!(evaluate("extra == 'dev.tools'", &["DEV__tools"]));
+        assert!(evaluate("extra != 'test'", &[]));
+        assert!(evaluate("extra == 'docs' or extra == 'test'", &["lint", "test"]));
+        // Every extra is evaluated on its own
+        assert!(!evaluate("extra == 'docs' and extra == 'test'", &["docs", "test"]));
+        // Extras are names, not versions
+        assert!(!evaluate("extra == '1.0'", &["1"]));
+    }
+
+    #[test]
+    fn test_requirement_applies() {
+        let environment = linux_cpython_311();
+        let requirements = ["numpy >=1.21",
+                            "tomli ; python_version < '3.11'",
+                            "pywin32 ; sys_platform == 'win32'",
+                            "pytest ; extra == 'test'"].map(|requirement| requirement.parse::<Requirement>().unwrap());
+        let applying = |extras: &[&str]| {
+            requirements.iter()
+                        .filter(|requirement| requirement.applies(&environment, extras))
+                        .map(|requirement| requirement.name.as_str())
+                        .collect::<Vec<&str>>()
+        };
+        assert_eq!(applying(&[]), vec!["numpy"]);
+        assert_eq!(applying(&["test"]), vec!["numpy", "pytest"]);
+    }
+}
+
+mod marker_algebra {
+    use rip::marker::{MarkerEnvironment, MarkerTree};
+
+    fn marker(marker: &str) -> MarkerTree {
+        marker.parse().unwrap()
+    }
+
+    fn simplify(marker_string: &str) -> String {
+        marker(marker_string).simplify().to_string()
+    }
+
+    #[test]
+    fn test_and_or() {
+        let both = marker("os_name == 'nt'").and(marker("python_version >= '3.8' and extra == 'test'"));
+        assert_eq!(both.to_string(),
+                   "os_name == \"nt\" and python_version >= \"3.8\" and extra == \"test\"");
+        let either = marker("os_name == 'nt' or os_name == 'posix'").or(marker("sys_platform == 'linux'"));
+        assert_eq!(either.to_string(),
+                   "os_name == \"nt\" or os_name == \"posix\" or sys_platform == \"linux\"");
+        assert_eq!(MarkerTree::TRUE.and(marker("os_name == 'nt'")).simplify(),
+                   marker("os_name == 'nt'"));
+    }
+
+    #[test]
+    fn test_tautologies_and_contradictions() {
+        assert_eq!(marker("python_version >= '3.8' or python_version < '3.8'").simplify(),
+                   MarkerTree::TRUE);
+        assert_eq!(marker("python_version >= '3.8' and python_version < '3.8'").simplify(),
+                   MarkerTree::FALSE);
+        assert_eq!(marker("'3.8' <= python_version or python_version < '3.8'").simplify(),
+                   MarkerTree::TRUE);
+        assert_eq!(marker("'3.8' > python_version and python_version >= '3.8'").simplify(),
+                   MarkerTree::FALSE);
+        assert_eq!(marker("os_name == 'nt' or os_name != 'nt'").simplify(), MarkerTree::TRUE);
+        assert_eq!(marker("os_name == 'nt' and os_name == 'posix'").simplify(), MarkerTree::FALSE);
+        assert_eq!(marker("extra == 'Dev_Tools' and extra != 'dev-tools'").simplify(),
+                   MarkerTree::FALSE);
+        assert_eq!(marker("'linux' in sys_platform or 'linux' not in sys_platform").simplify(),
+                   MarkerTree::TRUE);
+        assert_eq!(marker("platform_release < 'b' or platform_release >= 'b'").simplify(),
+                   MarkerTree::TRUE);
+        assert_eq!(marker("os_name ~= 'nt'").simplify(), MarkerTree::FALSE);
+        assert_eq!(marker("'a' == 'a'").simplify(), MarkerTree::TRUE);
+        assert_eq!(MarkerTree::TRUE.to_string(), "\"\" == \"\"");
+        assert_eq!(MarkerTree::FALSE.to_string(), "\"\" != \"\"");
+        assert!(marker(&MarkerTree::TRUE.to_string()).is_true());
+        assert!(marker(&MarkerTree::FALSE.to_string()).is_false());
+    }
+
+    #[test]
+    fn test_canonical_dnf() {
+        assert_eq!(simplify("python_version >= '3.8' and python_version < '4' and python_version >= '3.9'"),
+                   "python_version ~= \"3.9\"");
+        assert_eq!(simplify("python_version == '3.*' or python_version > '3.9'"),
+                   "python_version >= \"3\"");
+        assert_eq!(simplify("python_version < '3.8' or python_version == '3.8' or python_version > '3.8'"),
+                   "\"\" == \"\"");
+        assert_eq!(simplify("python_version < '3.8' or python_version == '3.8'"),
+                   "python_version <= \"3.8\"");
+        assert_eq!(simplify("(os_name == 'nt' or os_name == 'posix') and python_version >= '3.8'"),
+                   "os_name == \"nt\" and python_version >= \"3.8\" or os_name == \"posix\" and python_version >= \"3.8\"");
+        // Conjunctions implied by others are absorbed
+        assert_eq!(simplify("sys_platform == 'linux' or sys_platform == 'linux' and python_version < '3.8'"),
+                   "sys_platform == \"linux\"");
+        assert_eq!(simplify("python_version < '3.7' or python_version >= '3.9'"),
+                   "python_version < \"3.7\" or python_version >= \"3.9\"");
+        assert_eq!(simplify("os_name != 'nt' and os_name != 'java'"),
+                   "os_name != \"java\" and os_name != \"nt\"");
+        assert_eq!(simplify("extra == 'Test' and python_full_version >= '3.8.1'"),
+                   "python_full_version >= \"3.8.1\" and extra == \"test\"");
+        assert_eq!(simplify("implementation_name < 'c' and implementation_name > 'a'"),
+                   "implementation_name < \"c\" and implementation_name > \"a\"");
+
+        // Markers holding in the same environments give the same string
+        for (this, that) in
+            [("python_version >= '3.8' and os_name == 'nt'", "os_name == 'nt' and python_version >= '3.8'"),
+             ("(sys_platform == 'linux' or sys_platform == 'darwin') and python_version < '3.10'",
+              "python_version < '3.10' and sys_platform == 'darwin' or sys_platform == 'linux' and python_version < '3.10'"),
+             ("python_version >= '3.8' and python_version != '3.9'",
+              "python_version == '3.8' or python_version > '3.8' and python_version != '3.9'")]
+        {
+            assert_eq!(simplify(this), simplify(that));
+        }
+        let simplified = marker("python_version >= '3.8' and (os_name == 'nt' or python_version < '3.8')").simplify();
+        assert_eq!(simplified, marker(&simplified.to_string()).simplify());
+    }
+
+    #[test]
+    fn test_implies() {
+        assert!(marker("python_version >= '3.9'").implies(&marker("python_version >= '3.8'")));
+        assert!(!marker("python_version >= '3.8'").implies(&marker("python_version >= '3.9'")));
+        assert!(marker("os_name == 'nt' and python_version >= '3.9'").implies(&marker("os_name == 'nt'")));
+        assert!(marker("sys_platform == 'linux'").implies(&marker("sys_platform == 'linux' or sys_platform == 'darwin'")));
+        assert!(marker("sys_platform == 'linux'").implies(&marker("sys_platform != 'win32'")));
+        assert!(marker("python_version == '3.8'").implies(&marker("python_version < '3.9' and python_version >= '3'")));
+        assert!(marker("extra == 'test'").implies(&marker("extra != 'docs'")));
+        assert!(!marker("os_name == 'nt'").implies(&marker("sys_platform == 'win32'")));
+        assert!(marker("'linux' in sys_platform").implies(&marker("'linux' in sys_platform or os_name == 'nt'")));
+        assert!(MarkerTree::FALSE.implies(&marker("os_name == 'nt'")));
+        assert!(marker("os_name == 'nt'").implies(&MarkerTree::TRUE));
+
+        assert!(marker("python_version >= '3.8' or python_version < '3.8'").is_true());
+        assert!(!marker("python_version >= '3.8'").is_true());
+        assert!(marker("python_version < '3' and python_version > '3.8'").is_false());
+        assert!(!marker("python_version < '3' or python_version > '3.8'").is_false());
+    }
+
+    #[test]
+    fn test_simplify_keeps_evaluation() {
+        let environments = [("3.10", "1.0.5", "3.8"),
+                            ("3.*", "1.0.*", "3.11"),
+                            ("5.10.0", "#1 SMP", "3.7"),
+                            ("3", "1.0", "3.8")];
+        for marker_string in ["'3.*' == platform_release",
+                              "'1.0.*' != platform_version",
+                              "'3.*' == platform_release or platform_release == '3.10'",
+                              "'3.8' <= python_version",
+                              "'3.8' > python_version or os_name == 'nt'",
+                              "'3.8' == python_version",
+                              "'3.8' != python_version and '3.*' != platform_release",
+                              "'5.10.0' == platform_release",
+                              "'linux' == platform_release or platform_release == 'linux'"]
+        {
+            let original = marker(marker_string);
+            let simplified = original.simplify();
+            for (platform_release, platform_version, python_version) in environments {
+                let environment = MarkerEnvironment { platform_release: platform_release.to_string(),
+                                                      platform_version: platform_version.to_string(),
+                                                      python_version: python_version.to_string(),
+                                                      ..MarkerEnvironment::default() };
+                assert_eq!(simplified.evaluate(&environment, &[]),
+                           original.evaluate(&environment, &[]),
+                           "{} simplified to {} in {:?}",
+                           marker_string,
+                           simplified,
+                           environment);
+            }
+            assert!(original.implies(&simplified));
+            assert!(simplified.implies(&original));
+        }
+    }
+
+    #[test]
+    fn test_requirement_with_simplified_marker() {
+        let mut requirement: rip::requirement::Requirement =
+            "numpy >=1.21 ; python_version >= '3.8' or python_version < '3.8'".parse()
+                                                                              .unwrap();
+        requirement.marker = requirement.marker.map(|marker| marker.simplify());
+        assert_eq!(requirement.to_string(), "numpy>=1.21");
+    }
+}
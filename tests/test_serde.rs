@@ -0,0 +1,100 @@
+#![cfg(feature = "serde")]
+
+mod version_serde {
+    use rip::{AnyVersion, Version};
+
+    #[test]
+    fn test_version_serializes_as_canonical_string() {
+        let version: Version = "v1.0-ALPHA.1+Local".parse().unwrap();
+        assert_eq!(serde_json::to_string(&version).unwrap(), r#""1.0a1+local""#);
+    }
+
+    #[test]
+    fn test_version_deserializes_from_string() {
+        let versions: Vec<Version> = serde_json::from_str(r#"["1.0", "2!1.0.post1"]"#).unwrap();
+        assert_eq!(versions, ["1.0".parse::<Version>().unwrap(), "2!1.0-1".parse().unwrap()]);
+        assert!(serde_json::from_str::<Version>(r#""1.x""#).is_err());
+        assert!(serde_json::from_str::<Version>("1").is_err());
+    }
+
+    #[test]
+    fn test_any_version_round_trip() {
+        let version: AnyVersion = serde_json::from_str(r#""1.0-SNAPSHOT""#).unwrap();
+        assert!(version.is_legacy());
+        assert_eq!(serde_json::to_string(&version).unwrap(), r#""1.0-SNAPSHOT""#);
+    }
+}
+
+mod metadata_serde {
+    #[test]
+    fn test_metadata_round_trip() {
+        let metadata = rip::parse_metadata("Metadata-Version: 2.1\nName: pandas\nVersion: 1.5.3".to_string()).unwrap();
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["metadata_version"], "2.1");
+        assert_eq!(json["name"], "pandas");
+        assert_eq!(json["version"], "1.5.3");
+        assert_eq!(json["summary"], serde_json::Value::Null);
+
+        let deserialized: rip::Metadata = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.version, metadata.version);
+        assert_eq!(deserialized.name, metadata.name);
+    }
+}
+
+mod specifier_serde {
+    use rip::specifier::VersionSpecifier;
+
+    #[test]
+    fn test_specifier_round_trip() {
+        let specifier: VersionSpecifier = serde_json::from_str(r#"">= 1.0""#).unwrap();
+        assert_eq!(serde_json::to_string(&specifier).unwrap(), r#"">=1.0""#);
+        assert!(serde_json::from_str::<VersionSpecifier>(r#""1.0""#).is_err());
+    }
+}
+
+mod specifier_set_serde {
+    use rip::specifier::SpecifierSet;
+
+    #[test]
+    fn test_specifier_set_round_trip() {
+        let specifiers: SpecifierSet = serde_json::from_str(r#""<2, >= 1.0""#).unwrap();
+        assert_eq!(serde_json::to_string(&specifiers).unwrap(), r#""<2,>=1.0""#);
+    }
+}
+
+mod requirement_serde {
+    use rip::requirement::Requirement;
+
+    #[test]
+    fn test_requirement_round_trip() {
+        let requirement: Requirement = serde_json::from_str(r#""requests [socks] (>=2.8.1) ; python_version<'3.8'""#).unwrap();
+        assert_eq!(serde_json::to_string(&requirement).unwrap(),
+                   r#""requests[socks]>=2.8.1; python_version < \"3.8\"""#);
+        assert!(serde_json::from_str::<Requirement>(r#""requests ; python_version""#).is_err());
+    }
+}
+
+mod marker_environment_serde {
+    use rip::marker::MarkerEnvironment;
+
+    #[test]
+    fn test_marker_environment_round_trip() {
+        let environment = MarkerEnvironment { python_version: "3.8".to_string(),
+                                              ..MarkerEnvironment::default() };
+        let json = serde_json::to_value(&environment).unwrap();
+        assert_eq!(json["python_version"], "3.8");
+        assert_eq!(serde_json::from_value::<MarkerEnvironment>(json).unwrap(), environment);
+    }
+}
+
+mod name_serde {
+    use rip::name::{ExtraName, PackageName};
+
+    #[test]
+    fn test_name_round_trip() {
+        let name: PackageName = serde_json::from_str(r#""Foo_Bar""#).unwrap();
+        assert_eq!(serde_json::to_string(&name).unwrap(), r#""Foo_Bar""#);
+        assert_eq!(name, "foo-bar".parse().unwrap());
+        assert!(serde_json::from_str::<ExtraName>(r#""-dev""#).is_err());
+    }
+}
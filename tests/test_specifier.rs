@@ -0,0 +1,304 @@
+mod specifier_parse {
+    use rip::specifier::{parse_version_specifier, Operator, VersionSpecifier};
+
+    #[test]
+    fn test_parse_operators() {
+        for (specifier, operator) in [("~=1.0", Operator::Compatible),
+                                      ("==1.0", Operator::Equal),
+                                      ("!=1.0", Operator::NotEqual),
+                                      ("<=1.0", Operator::LessThanEqual),
+                                      (">=1.0", Operator::GreaterThanEqual),
+                                      ("<1.0", Operator::LessThan),
+                                      (">1.0", Operator::GreaterThan),
+                                      ("===1.0", Operator::ArbitraryEqual)]
+        {
+            assert_eq!(parse_version_specifier(specifier.to_string()).unwrap().operator, operator);
+        }
+    }
+
+    #[test]
+    fn test_display_is_canonical() {
+        for (specifier, expected) in [(" >= 1.0-ALPHA.1 ", ">=1.0a1"),
+                                      ("~=v2.0.post1", "~=2.0.post1"),
+                                      ("==1.0+Ubuntu-1", "==1.0+ubuntu.1"),
+                                      ("=== foo-BAR ", "===foo-BAR"),
+                                      ("===1.0-alpha1", "===1.0-alpha1"),
+                                      ("!= 1.04.0.*", "!=1.4.0.*")]
+        {
+            let specifier: VersionSpecifier = specifier.parse().unwrap();
+            assert_eq!(specifier.to_string(), expected);
+            assert_eq!(specifier.to_string().parse::<VersionSpecifier>().unwrap(), specifier);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_version_specifier("  1.0 ".to_string()),
+                   Err(rip::Error::InvalidSpecifier { input: "  1.0 ".to_string(),
+                                                      span: 2..5,
+                                                      reason: "expected one of ~=, ==, !=, <=, >=, <, >, ===".to_string() }));
+        assert_eq!(parse_version_specifier(">= 1.x".to_string()),
+                   Err(rip::Error::InvalidVersion { input: ">= 1.x".to_string(),
+                                                    span: 3..6 }));
+        assert!(matches!(parse_version_specifier(">=1.0+local".to_string()),
+                         Err(rip::Error::InvalidSpecifier { span, .. }) if span == (2..11)));
+        assert!(matches!(parse_version_specifier("~=1".to_string()),
+                         Err(rip::Error::InvalidSpecifier { span, .. }) if span == (2..3)));
+        assert!(parse_version_specifier("=>1.0".to_string()).is_err());
+        assert!(parse_version_specifier("===".to_string()).is_err());
+        assert!(parse_version_specifier("==".to_string()).is_err());
+    }
+}
+
+mod specifier_contains {
+    use rip::specifier::VersionSpecifier;
+
+    fn check(specifier: &str, contained: &[&str], excluded: &[&str]) {
+        let specifier: VersionSpecifier = specifier.parse().unwrap();
+        for version in contained {
+            assert!(specifier.contains(&version.parse().unwrap()),
+                    "{} should contain {}",
+                    specifier,
+                    version);
+        }
+        for version in excluded {
+            assert!(!specifier.contains(&version.parse().unwrap()),
+                    "{} should not contain {}",
+                    specifier,
+                    version);
+        }
+    }
+
+    #[test]
+    fn test_equal() {
+        check("==2.0",
+              &["2", "2.0.0", "2.0+local"],
+              &["2.0.1", "2.0rc1", "2.0.post1", "1!2.0"]);
+        check("==2.0+local", &["2.0+local"], &["2.0", "2.0+other"]);
+        check("!=2.0", &["2.0.1", "2.0rc1"], &["2.0", "2.0+local"]);
+    }
+
+    #[test]
+    fn test_inclusive_ordered() {
+        check(">=2.0", &["2.0", "2.0.post1", "3.0", "2.0+local"], &["2.0rc1", "1.9"]);
+        check("<=2.0", &["2.0", "2.0rc1", "1.0", "2.0+local"], &["2.0.post1", "2.1"]);
+    }
+
+    #[test]
+    fn test_exclusive_ordered() {
+        check("<2.0", &["1.9", "1.9.post1", "1.0"], &["2.0", "2.0rc1", "2.0.dev1"]);
+        check("<2.0rc1", &["2.0b1", "2.0.dev0"], &["2.0rc1", "2.0"]);
+        check(">2.0", &["2.0.1", "3.0"], &["2.0", "2.0.post1", "2.0+local", "1.0"]);
+        check(">2.0.post1", &["2.0.post2", "2.1"], &["2.0.post1", "2.0"]);
+    }
+
+    #[test]
+    fn test_exclusive_ordered_only_exclude_releases_of_given_version() {
+        check("<2.0.post1",
+              &["2.0rc1", "2.0", "2.0.post0"],
+              &["2.0.post1.dev0", "2.0.post1"]);
+        check(">2.0rc1", &["2.0", "2.0.post1", "2.0rc2"], &["2.0rc1.post1", "2.0rc1+local"]);
+        check(">2.0.dev1",
+              &["2.0.dev2", "2.0", "2.0.1"],
+              &["2.0.post1", "2.0.post1.dev0", "2.0.dev1"]);
+    }
+
+    #[test]
+    fn test_compatible() {
+        check("~=2.2", &["2.2", "2.3", "2.9.post1"], &["3.0", "2.1", "2.2rc1"]);
+        check("~=1.4.5", &["1.4.5", "1.4.9"], &["1.5.0", "1.4.4"]);
+        check("~=1.4.5a4", &["1.4.5a4", "1.4.5", "1.4.6"], &["1.5.0", "1.4.5a3"]);
+        check("~=1!2.2", &["1!2.5"], &["2.5"]);
+    }
+
+    #[test]
+    fn test_arbitrary_equal() {
+        check("===1.0", &["1.0"], &["1.0.0", "1.0+local"]);
+        check("===1.0-ALPHA1", &["1.0-alpha1"], &["1.0a1"]);
+    }
+}
+
+mod specifier_wildcard {
+    use rip::specifier::{parse_version_specifier, VersionSpecifier};
+
+    fn specifier(specifier: &str) -> VersionSpecifier {
+        specifier.parse().unwrap()
+    }
+
+    fn contains(specifier_string: &str, version: &str) -> bool {
+        specifier(specifier_string).contains(&version.parse().unwrap())
+    }
+
+    #[test]
+    fn test_prefix_matching() {
+        for version in ["1.4", "1.4.0", "1.4.5", "1.4rc1", "1.4.post1", "1.4.dev0", "1.4.2+local"] {
+            assert!(contains("==1.4.*", version), "==1.4.* should contain {}", version);
+            assert!(!contains("!=1.4.*", version), "!=1.4.* should not contain {}", version);
+        }
+        for version in ["1.5", "1.40", "1", "2.4", "1!1.4"] {
+            assert!(!contains("==1.4.*", version), "==1.4.* should not contain {}", version);
+            assert!(contains("!=1.4.*", version), "!=1.4.* should contain {}", version);
+        }
+    }
+
+    #[test]
+    fn test_prefix_is_zero_padded() {
+        assert!(contains("==1.0.0.*", "1"));
+        assert!(contains("==1.0.0.*", "1.0"));
+        assert!(contains("==1.*", "1.0.0.1"));
+        assert!(!contains("==1.0.1.*", "1.0"));
+    }
+
+    #[test]
+    fn test_prefix_epochs() {
+        assert!(contains("==1!2.*", "1!2.3"));
+        assert!(!contains("==1!2.*", "2.3"));
+        assert!(contains("==0!2.*", "2.3"));
+    }
+
+    #[test]
+    fn test_local_labels_are_ignored_without_local_specifier() {
+        assert!(contains("==1.0", "1.0+ubuntu1"));
+        assert!(contains("==1.0.*", "1.0.3+ubuntu1"));
+        assert!(!contains("==1.0+ubuntu2", "1.0+ubuntu1"));
+        assert!(contains("!=1.0+ubuntu2", "1.0+ubuntu1"));
+    }
+
+    #[test]
+    fn test_trailing_zeros_are_significant() {
+        assert_eq!(specifier("==1.0"), specifier("==1.0.0"));
+        assert_ne!(specifier("==1.4.*"), specifier("==1.4.0.*"));
+        assert_ne!(specifier("~=1.4"), specifier("~=1.4.0"));
+        assert_ne!(specifier("==1.4.*"), specifier("==1.4"));
+    }
+
+    #[test]
+    fn test_invalid_wildcards() {
+        for (specifier, span) in [(">=1.0.*", 2..7),
+                                  ("~=1.0.*", 2..7),
+                                  ("< 1.*", 2..5),
+                                  ("==1.0a1.*", 2..9),
+                                  ("!=1.0+local.*", 2..13)]
+        {
+            assert!(matches!(parse_version_specifier(specifier.to_string()),
+                             Err(rip::Error::InvalidSpecifier { span: error_span, .. }) if error_span == span),
+                    "{} should be rejected",
+                    specifier);
+        }
+        assert!(matches!(parse_version_specifier("==1.*.*".to_string()),
+                         Err(rip::Error::InvalidVersion { .. })));
+        assert!(matches!(parse_version_specifier("==*".to_string()),
+                         Err(rip::Error::InvalidVersion { .. })));
+        assert!(parse_version_specifier("===1.0.*".to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(parse_version_specifier(">=1.0.*".to_string()).unwrap_err().to_string(),
+                   ">=1.0.* is not a valid version specifier: wildcards are only allowed with == and !=, not >=");
+        assert_eq!(parse_version_specifier("~=1".to_string()).unwrap_err().to_string(),
+                   "~=1 is not a valid version specifier: ~= needs a version with at least two release segments, e.g. ~=1.0");
+    }
+}
+
+mod specifier_set {
+    use rip::specifier::{parse_specifier_set, SpecifierSet};
+    use rip::Version;
+
+    fn versions(versions: &[&str]) -> Vec<Version> {
+        versions.iter().map(|version| version.parse().unwrap()).collect()
+    }
+
+    fn filtered(specifiers: &SpecifierSet, candidates: &[&str]) -> Vec<String> {
+        specifiers.filter(&versions(candidates))
+                  .into_iter()
+                  .map(|version| version.to_string())
+                  .collect()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let specifiers: SpecifierSet = " >= 1.2 ,!=1.3.*,, <2 ".parse().unwrap();
+        assert_eq!(specifiers.specifiers.len(), 3);
+        assert_eq!(specifiers.to_string(), ">=1.2,!=1.3.*,<2");
+        assert_eq!("".parse::<SpecifierSet>().unwrap(), SpecifierSet::default());
+    }
+
+    #[test]
+    fn test_parse_error_spans() {
+        assert_eq!(parse_specifier_set(">=1.0, <2.x".to_string()),
+                   Err(rip::Error::InvalidVersion { input: ">=1.0, <2.x".to_string(),
+                                                    span: 8..11 }));
+        assert!(matches!(parse_specifier_set(">=1.0,2.0".to_string()),
+                         Err(rip::Error::InvalidSpecifier { span, .. }) if span == (6..9)));
+    }
+
+    #[test]
+    fn test_contains_all() {
+        let specifiers: SpecifierSet = ">=1.2,!=1.3.*,<2".parse().unwrap();
+        for (version, expected) in [("1.2", true),
+                                    ("1.4.1", true),
+                                    ("1.3.5", false),
+                                    ("2.0", false),
+                                    ("1.1", false)]
+        {
+            assert_eq!(specifiers.contains(&version.parse().unwrap()), expected, "{}", version);
+        }
+        assert!(SpecifierSet::default().contains(&"1.0".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_prereleases_excluded_by_default() {
+        let specifiers: SpecifierSet = ">=1.0".parse().unwrap();
+        assert_eq!(specifiers.prereleases(), None);
+        assert!(!specifiers.contains(&"2.0rc1".parse().unwrap()));
+        assert!(!specifiers.contains(&"2.0.dev1".parse().unwrap()));
+        assert_eq!(filtered(&specifiers, &["1.0", "2.0rc1", "1.5"]), ["1.0", "1.5"]);
+    }
+
+    #[test]
+    fn test_prereleases_when_only_match() {
+        let specifiers: SpecifierSet = ">=2.0.dev0".parse().unwrap();
+        assert_eq!(specifiers.prereleases(), Some(true));
+
+        let specifiers: SpecifierSet = ">1.0".parse().unwrap();
+        assert_eq!(filtered(&specifiers, &["1.0", "2.0b1", "2.0rc1"]), ["2.0b1", "2.0rc1"]);
+        assert_eq!(filtered(&specifiers, &["2.0b1", "1.5"]), ["1.5"]);
+    }
+
+    #[test]
+    fn test_prereleases_requested_by_specifier() {
+        let specifiers: SpecifierSet = ">=1.0rc1".parse().unwrap();
+        assert!(specifiers.contains(&"1.1a1".parse().unwrap()));
+        assert_eq!(filtered(&specifiers, &["1.0", "1.1a1"]), ["1.0", "1.1a1"]);
+
+        let specifiers: SpecifierSet = "!=1.0rc1,<1.0rc1".parse().unwrap();
+        assert_eq!(specifiers.prereleases(), None);
+    }
+
+    #[test]
+    fn test_allow_prereleases_override() {
+        let mut specifiers: SpecifierSet = ">=1.0".parse().unwrap();
+        specifiers.allow_prereleases = Some(true);
+        assert!(specifiers.contains(&"2.0rc1".parse().unwrap()));
+        assert_eq!(filtered(&specifiers, &["1.0", "2.0rc1"]), ["1.0", "2.0rc1"]);
+
+        let mut specifiers: SpecifierSet = ">=1.0rc1".parse().unwrap();
+        specifiers.allow_prereleases = Some(false);
+        assert!(!specifiers.contains(&"1.0rc1".parse().unwrap()));
+        assert!(filtered(&specifiers, &["1.0rc1", "1.1b1"]).is_empty());
+    }
+
+    #[test]
+    fn test_best_match() {
+        let candidates = versions(&["1.0", "1.4.2", "1.10", "2.0", "2.1rc1", "1.9.post1"]);
+        let specifiers: SpecifierSet = ">=1.0,<2".parse().unwrap();
+        assert_eq!(specifiers.best_match(&candidates).unwrap().to_string(), "1.10");
+        let specifiers: SpecifierSet = "~=1.4.0".parse().unwrap();
+        assert_eq!(specifiers.best_match(candidates.clone()).unwrap().to_string(), "1.4.2");
+        let specifiers: SpecifierSet = ">2.0".parse().unwrap();
+        assert_eq!(specifiers.best_match(&candidates).unwrap().to_string(), "2.1rc1");
+        let specifiers: SpecifierSet = ">3".parse().unwrap();
+        assert!(specifiers.best_match(&candidates).is_none());
+    }
+}
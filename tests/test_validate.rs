@@ -0,0 +1,120 @@
+mod metadata_validate {
+    use rip::validate::{validate_metadata, Severity, Violation};
+    use rip::Error;
+
+    fn violations(metadata: &str) -> Vec<(Severity, String)> {
+        validate_metadata(metadata.to_string()).unwrap()
+                                               .violations
+                                               .iter()
+                                               .map(|violation| (violation.severity, violation.to_string()))
+                                               .collect()
+    }
+
+    #[test]
+    fn test_valid_metadata() {
+        let report = validate_metadata("Metadata-Version: 2.4\nName: rip\nVersion: 1.0\nLicense-Expression: MIT\n\
+                                        Provides-Extra: dev-tools\nRequires-Dist: pytest; extra == 'dev-tools'\n\n\
+                                        # rip\n"
+                                                .to_string()).unwrap();
+        assert_eq!(report.violations, []);
+        assert!(report.is_valid());
+        assert_eq!(report.metadata_version.to_string(), "2.4");
+    }
+
+    #[test]
+    fn test_unknown_major_version() {
+        assert!(matches!(validate_metadata("Metadata-Version: 3.0\nName: rip\nVersion: 1.0\n".to_string()),
+                         Err(Error::UnsupportedMetadataVersion { span, .. }) if span == (18..21)));
+    }
+
+    #[test]
+    fn test_unknown_minor_versions() {
+        assert_eq!(violations("Metadata-Version: 2.0\nName: rip\nVersion: 1.0\n"),
+                   [(Severity::Warning,
+                     "warning: Metadata-Version 2.0 is not defined by the core metadata specification".to_string())]);
+        assert_eq!(violations("Metadata-Version: 2.5\nName: rip\nVersion: 1.0\n"),
+                   [(Severity::Warning,
+                     "warning: Metadata-Version 2.5 is newer than 2.4, fields it introduced are not checked".to_string())]);
+    }
+
+    #[test]
+    fn test_fields_used_before_their_version() {
+        let report = validate_metadata("Metadata-Version: 1.0\nName: rip\nVersion: 1.0\nRequires-Dist: numpy\n\
+                                        Description-Content-Type: text/markdown\nDynamic: Summary\n"
+                                                                                                    .to_string()).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.warnings()
+                         .map(|violation| violation.to_string())
+                         .collect::<Vec<String>>(),
+                   ["warning: Requires-Dist is only defined since Metadata-Version 1.2",
+                    "warning: Description-Content-Type is only defined since Metadata-Version 2.1",
+                    "warning: Dynamic is only defined since Metadata-Version 2.2"]);
+        assert_eq!(report.violations[0].span, 45..65);
+    }
+
+    #[test]
+    fn test_deprecated_and_unknown_fields() {
+        assert_eq!(violations("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nRequires: numpy\nX-Tool: 1\n"),
+                   [(Severity::Warning, "warning: Requires is deprecated since Metadata-Version 1.2".to_string()),
+                    (Severity::Warning, "warning: X-Tool is not a core metadata field".to_string())]);
+        assert_eq!(violations("Metadata-Version: 2.4\nName: rip\nVersion: 1.0\nLicense: MIT\n"),
+                   [(Severity::Warning, "warning: License is deprecated since Metadata-Version 2.4".to_string())]);
+    }
+
+    #[test]
+    fn test_repeated_single_use_fields() {
+        let metadata = "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nsummary: one\nSummary: two\nClassifier: a\n\
+                        Classifier: b\n";
+        let report = validate_metadata(metadata.to_string()).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.errors().cloned().collect::<Vec<Violation>>(),
+                   [Violation { severity: Severity::Error,
+                                field: "Summary".to_string(),
+                                span: 58..70,
+                                reason: "may only be used once".to_string() }]);
+    }
+
+    #[test]
+    fn test_body_description() {
+        assert_eq!(violations("Metadata-Version: 1.2\nName: rip\nVersion: 1.0\n\n# rip\n"),
+                   [(Severity::Warning,
+                     "warning: Description is only allowed in the message body since Metadata-Version 2.1".to_string())]);
+        assert_eq!(violations("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nDescription: rip\n\n# rip\n"),
+                   [(Severity::Error, "error: Description is given both as a field and as the message body".to_string())]);
+    }
+
+    #[test]
+    fn test_dynamic_fields() {
+        assert_eq!(violations("Metadata-Version: 2.2\nName: rip\nVersion: 1.0\nDynamic: Requires-Dist\nDynamic: version\n\
+                               Dynamic: Home-Page-URL\n"),
+                   [(Severity::Error, "error: Dynamic must not list version".to_string()),
+                    (Severity::Error, "error: Dynamic lists Home-Page-URL, which is not a core metadata field".to_string())]);
+    }
+
+    #[test]
+    fn test_normalized_extras() {
+        let metadata = "Metadata-Version: {}\nName: rip\nVersion: 1.0\nProvides-Extra: Dev_Tools\n";
+        assert_eq!(violations(&metadata.replace("{}", "2.1")), []);
+        assert_eq!(violations(&metadata.replace("{}", "2.3")),
+                   [(Severity::Error,
+                     "error: Provides-Extra must be normalized to dev-tools since Metadata-Version 2.3".to_string())]);
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        assert_eq!(violations("Metadata-Version: 1.2\nName: rip\nVersion: 1.0\nRequires-Dist: foo (>=1.0-SNAPSHOT-2)\n\
+                               Requires-Python: >= 2.4.*\nProject-URL: https://example.com\nRequires-Dist: bar (\n"),
+                   [(Severity::Error, "error: Requires-Dist has the invalid version 1.0-SNAPSHOT-2".to_string()),
+                    (Severity::Error,
+                     "error: Requires-Python is invalid: wildcards are only allowed with == and !=, not >=".to_string()),
+                    (Severity::Error, "error: Project-URL must be of the form `label, url`".to_string()),
+                    (Severity::Error, "error: Requires-Dist is invalid: expected ) to close this (".to_string())]);
+    }
+
+    #[test]
+    fn test_license_and_license_expression() {
+        assert_eq!(violations("Metadata-Version: 2.4\nName: rip\nVersion: 1.0\nLicense-Expression: MIT\nLicense: MIT\n"),
+                   [(Severity::Error, "error: License-Expression must not be used together with License".to_string()),
+                    (Severity::Warning, "warning: License is deprecated since Metadata-Version 2.4".to_string())]);
+    }
+}
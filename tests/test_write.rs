@@ -0,0 +1,119 @@
+mod metadata_write {
+    use rip::parse_metadata;
+
+    const PIP_METADATA: &str = "Metadata-Version: 2.2
+Name: pip
+Version: 23.0
+Dynamic: Requires-Dist
+Platform: any
+Summary: The PyPA recommended tool for installing Python packages.
+Description-Content-Type: text/x-rst
+Keywords: packaging,installer
+Home-page: https://pip.pypa.io/
+Author: The pip developers
+Author-email: distutils-sig@python.org
+License: MIT
+Classifier: Development Status :: 5 - Production/Stable
+Classifier: License :: OSI Approved :: MIT License
+Requires-Dist: pytest; extra == \"dev-tools\"
+Requires-Dist: rich>=12
+Requires-Python: >=3.7
+Project-URL: Documentation, https://pip.pypa.io
+Project-URL: Source, https://github.com/pypa/pip
+Provides-Extra: dev-tools
+
+pip
+===
+
+The PyPA recommended tool for installing Python packages.
+";
+
+    #[test]
+    fn test_write_round_trip() {
+        let metadata = parse_metadata(PIP_METADATA.to_string()).unwrap();
+        assert_eq!(metadata.to_string(), PIP_METADATA);
+        assert_eq!(parse_metadata(metadata.to_string()).unwrap(), metadata);
+        assert_eq!(rip::validate::validate_metadata(metadata.to_string()).unwrap().violations, []);
+    }
+
+    #[test]
+    fn test_write_in_specification_order() {
+        let metadata = parse_metadata("Metadata-Version: 2.1\nProvides-Extra: test\nversion: 1.0\nClassifier: b\n\
+                                       Summary: rip\nname: rip\nClassifier: a\n"
+                                                                                .to_string()).unwrap();
+        assert_eq!(metadata.to_string(),
+                   "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nSummary: rip\nClassifier: b\nClassifier: a\n\
+                    Provides-Extra: test\n");
+    }
+
+    #[test]
+    fn test_write_raises_metadata_version() {
+        let mut metadata = parse_metadata("Metadata-Version: 1.0\nName: rip\nVersion: 1.0\n".to_string()).unwrap();
+        assert_eq!(metadata.to_string(), "Metadata-Version: 1.0\nName: rip\nVersion: 1.0\n");
+
+        metadata.requires_dist = Some(vec!["numpy".parse().unwrap()]);
+        assert!(metadata.to_string().starts_with("Metadata-Version: 1.2\n"));
+
+        metadata.dynamic = Some(vec!["Summary".to_string()]);
+        let written = parse_metadata(metadata.to_string()).unwrap();
+        assert_eq!(written.metadata_version.to_string(), "2.2");
+        assert_eq!(written.dynamic, metadata.dynamic);
+    }
+
+    #[test]
+    fn test_write_multi_line_values() {
+        let mut metadata = parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\n".to_string()).unwrap();
+        metadata.summary = Some("line1\nline2".to_string());
+        metadata.license = Some("MIT License\n\n    Copyright (c) rip".to_string());
+        metadata.classifiers = Some(vec!["Private :: Do Not Upload\nTwice".to_string()]);
+        assert_eq!(metadata.to_string(),
+                   "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nSummary: line1\n        line2\nLicense: MIT License\n        \n            \
+                    Copyright (c) rip\nClassifier: Private :: Do Not Upload\n        Twice\n");
+        assert_eq!(parse_metadata(metadata.to_string()).unwrap(), metadata);
+    }
+
+    #[test]
+    fn test_read_folded_values() {
+        let metadata = parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nSummary: a very\r\n  long summary\n\
+                                       Author: a\n\tb\n"
+                                                        .to_string()).unwrap();
+        assert_eq!(metadata.summary.unwrap(), "a very\nlong summary");
+        assert_eq!(metadata.author.unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn test_write_keywords() {
+        let mut metadata = parse_metadata("Metadata-Version: 2.1\nName: rip\nVersion: 1.0\n".to_string()).unwrap();
+        for keywords in [vec!["machine learning"], vec!["machine learning", "ai"], vec!["packaging"]] {
+            metadata.keywords = Some(keywords.into_iter().map(str::to_string).collect());
+            assert_eq!(parse_metadata(metadata.to_string()).unwrap(), metadata);
+        }
+        metadata.keywords = Some(vec!["machine learning".to_string()]);
+        assert!(metadata.to_string().contains("\nKeywords: machine learning,\n"));
+        metadata.keywords = Some(vec!["packaging".to_string()]);
+        assert!(metadata.to_string().contains("\nKeywords: packaging\n"));
+    }
+
+    #[test]
+    fn test_write_legacy_description() {
+        let mut metadata = parse_metadata("Metadata-Version: 1.2\nName: rip\nVersion: 1.0\n".to_string()).unwrap();
+        metadata.description = Some("rip\n===\n\n    indented\n".to_string());
+        metadata.license = Some("MIT".to_string());
+        assert_eq!(metadata.to_string(),
+                   "Metadata-Version: 1.2\nName: rip\nVersion: 1.0\nDescription: rip\n       |===\n       |\n       |    \
+                    indented\n       |\nLicense: MIT\n");
+        assert_eq!(parse_metadata(metadata.to_string()).unwrap(), metadata);
+    }
+
+    #[test]
+    fn test_write_body_description() {
+        let mut metadata = parse_metadata("Metadata-Version: 1.2\nName: rip\nVersion: 1.0\n".to_string()).unwrap();
+        metadata.description = Some("# rip\n".to_string());
+        metadata.description_content_type = Some("text/markdown".to_string());
+        assert_eq!(metadata.to_string(),
+                   "Metadata-Version: 2.1\nName: rip\nVersion: 1.0\nDescription-Content-Type: text/markdown\n\n# rip\n");
+        let written = parse_metadata(metadata.to_string()).unwrap();
+        assert_eq!(written.description, metadata.description);
+        assert_eq!(written.description_content_type, metadata.description_content_type);
+    }
+}